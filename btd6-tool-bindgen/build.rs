@@ -0,0 +1,35 @@
+use std::{env, path::PathBuf};
+
+/// Tells the macros where to find Il2CppDumper's dumps, one `<version>.cs` per game version,
+/// rebuilding them whenever a dump is added or changed
+fn main() {
+    println!("cargo:rerun-if-env-changed=BTD6_DUMPS");
+
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+
+    let dir = match env::var_os("BTD6_DUMPS") {
+        Some(dir) => manifest_dir.join(dir),
+        None => manifest_dir.join("dumps"),
+    };
+
+    match std::fs::read_dir(&dir) {
+        Ok(entries) => {
+            println!("cargo:rerun-if-changed={}", dir.display());
+
+            for entry in entries.flatten() {
+                println!("cargo:rerun-if-changed={}", entry.path().display());
+            }
+        }
+
+        // watch for the directory being created instead
+        Err(_) => match dir.parent() {
+            Some(parent) if parent.exists() => {
+                println!("cargo:rerun-if-changed={}", parent.display())
+            }
+
+            _ => {}
+        },
+    }
+
+    println!("cargo:rustc-env=BTD6_DUMPS={}", dir.display());
+}
@@ -0,0 +1,114 @@
+use std::{cmp::Ordering, fs, path::Path, sync::OnceLock};
+
+use btd6_tool_dump::Dump;
+use proc_macro2::Span;
+use syn::Error;
+
+/// where build.rs found the dumps, `btd6-tool-bindgen/dumps` unless `BTD6_DUMPS` is set
+const DUMPS_DIR: &str = env!("BTD6_DUMPS");
+
+/// maps the build id of each GameAssembly.dll to the version of its dump, one `<build id> <version>`
+/// per line
+const BUILDS_FILE: &str = "builds.txt";
+
+/// Il2CppDumper's dump.cs of one version of the game
+pub struct VersionDump {
+    pub version: String,
+    pub dump: Dump,
+}
+
+/// every dump, parsed once and shared by every expansion in the crate being compiled, newest first
+pub fn dumps() -> syn::Result<&'static [VersionDump]> {
+    static PARSED: OnceLock<Result<Vec<VersionDump>, String>> = OnceLock::new();
+
+    match PARSED.get_or_init(|| load_dumps(Path::new(DUMPS_DIR))) {
+        Ok(dumps) => Ok(dumps),
+        Err(message) => Err(Error::new(Span::call_site(), message)),
+    }
+}
+
+fn load_dumps(dir: &Path) -> Result<Vec<VersionDump>, String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(vec![]);
+    };
+
+    let mut dumps = vec![];
+
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("failed to list {}: {}", dir.display(), e))?
+            .path();
+
+        if path.extension().is_none_or(|e| e != "cs") {
+            continue;
+        }
+
+        let Some(version) = path.file_stem().map(|v| v.to_string_lossy().into_owned()) else {
+            continue;
+        };
+
+        let text = fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let dump = Dump::parse(&text)
+            .map_err(|e| format!("failed to parse {}: {:#}", path.display(), e))?;
+
+        dumps.push(VersionDump { version, dump });
+    }
+
+    dumps.sort_by(|a, b| compare_versions(&b.version, &a.version));
+    Ok(dumps)
+}
+
+/// the game version of each build id listed in the dumps' builds.txt
+pub fn builds() -> syn::Result<Vec<(String, String)>> {
+    let path = Path::new(DUMPS_DIR).join(BUILDS_FILE);
+
+    let Ok(text) = fs::read_to_string(&path) else {
+        return Ok(vec![]);
+    };
+
+    let dumps = dumps()?;
+
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let Some((build_id, version)) = line.split_once(char::is_whitespace) else {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!(
+                        "invalid line in {}, expected `<build id> <version>`: {}",
+                        path.display(),
+                        line
+                    ),
+                ));
+            };
+
+            let version = version.trim();
+            if !dumps.iter().any(|d| d.version == version) {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!(
+                        "{} lists version {} with no {}.cs",
+                        path.display(),
+                        version,
+                        version
+                    ),
+                ));
+            }
+
+            Ok((build_id.to_lowercase(), version.to_string()))
+        })
+        .collect()
+}
+
+/// orders versions like `45.2` and `46.0` by each number in turn
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| {
+        v.split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect::<Vec<_>>()
+    };
+
+    parts(a).cmp(&parts(b)).then_with(|| a.cmp(b))
+}
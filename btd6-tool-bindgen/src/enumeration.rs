@@ -0,0 +1,431 @@
+use btd6_tool_dump::Class;
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    punctuated::Punctuated, Error, Expr, ExprLit, ExprUnary, ItemEnum, Lit, LitStr, Meta, UnOp,
+};
+
+use crate::{dumps, string_literal, suggest};
+
+/// the Rust type read for each underlying type of a C# enum
+const UNDERLYING_TYPES: &[(&str, &str)] = &[
+    ("byte", "u8"),
+    ("sbyte", "i8"),
+    ("short", "i16"),
+    ("ushort", "u16"),
+    ("int", "i32"),
+    ("uint", "u32"),
+    ("long", "i64"),
+    ("ulong", "u64"),
+];
+
+/// the variant holding values of an enum that aren't one of its members
+const OTHER: &str = "Other";
+
+/// A member of a C# enum
+struct Member {
+    name: String,
+    value: i128,
+    /// where the member was written, if it was
+    span: Span,
+}
+
+pub fn expand(
+    arguments: &Punctuated<Meta, syn::Token![,]>,
+    item: &ItemEnum,
+) -> syn::Result<TokenStream> {
+    let mut namespace = None;
+    let mut rename = None;
+    let mut flags = false;
+
+    for attr in arguments.iter() {
+        match attr {
+            Meta::Path(path) if path.is_ident("flags") => flags = true,
+
+            Meta::NameValue(value) if value.path.is_ident("namespace") => {
+                namespace = Some(string_literal(&value.value)?.value());
+            }
+
+            Meta::NameValue(value) if value.path.is_ident("rename") => {
+                rename = Some(string_literal(&value.value)?);
+            }
+
+            _ => {
+                return Err(Error::new_spanned(
+                    attr,
+                    "unknown argument, expected `namespace`, `rename` or `flags`",
+                ))
+            }
+        }
+    }
+
+    let Some(namespace) = namespace else {
+        return Err(Error::new(
+            Span::call_site(),
+            "missing argument `namespace = \"...\"`",
+        ));
+    };
+
+    let name = &item.ident;
+    let csharp_name = rename.map_or(name.to_string(), LitStr::value);
+    let span = rename.map_or(name.span(), LitStr::span);
+
+    let declared = declared_members(item)?;
+
+    let dumps = dumps::dumps()?;
+    let classes = dumps
+        .iter()
+        .filter_map(|d| Some((d, d.dump.get_class(&namespace, &csharp_name).ok()?)))
+        .collect::<Vec<_>>();
+
+    if let (Some(newest), true) = (dumps.first(), classes.is_empty()) {
+        return Err(crate::class_error(
+            &newest.dump,
+            &namespace,
+            &csharp_name,
+            span,
+        ));
+    }
+
+    // every dump must agree with the members the Rust code relies on, and the newest supplies
+    // the rest
+    for (dump, class) in &classes {
+        check_class(class, flags, &declared, span).map_err(|e| {
+            Error::new(e.span(), format!("{} (in the dump of {})", e, dump.version))
+        })?;
+    }
+
+    let (underlying, members) = match classes.first() {
+        Some((_, class)) => (class.enum_type().unwrap(), members(class)?),
+        None => ("int", declared),
+    };
+
+    let Some((_, underlying)) = UNDERLYING_TYPES.iter().find(|(c, _)| *c == underlying) else {
+        return Err(Error::new(
+            span,
+            format!("unsupported underlying type `{}`", underlying),
+        ));
+    };
+
+    let underlying = Ident::new(underlying, Span::call_site());
+
+    let members = members
+        .iter()
+        .filter_map(|m| Some((member_ident(&m.name)?, m)))
+        .collect::<Vec<_>>();
+
+    match flags {
+        true => Ok(expand_flags(item, &underlying, &members)),
+        false => expand_enum(item, &underlying, &members),
+    }
+}
+
+/// members written in the Rust enum, used when there are no dumps and checked against any there are
+fn declared_members(item: &ItemEnum) -> syn::Result<Vec<Member>> {
+    item.variants
+        .iter()
+        .map(|variant| {
+            let value = match &variant.discriminant {
+                Some((_, expr)) => integer(expr)?,
+                None => {
+                    return Err(Error::new_spanned(
+                        variant,
+                        "expected the member's value, e.g. `Hero = 16`",
+                    ))
+                }
+            };
+
+            Ok(Member {
+                name: variant.ident.to_string(),
+                value,
+                span: variant.ident.span(),
+            })
+        })
+        .collect()
+}
+
+fn integer(expr: &Expr) -> syn::Result<i128> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(value),
+            ..
+        }) => value.base10_parse(),
+
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => Ok(-integer(expr)?),
+
+        expr => Err(Error::new_spanned(expr, "expected an integer")),
+    }
+}
+
+fn check_class(class: &Class, flags: bool, declared: &[Member], span: Span) -> syn::Result<()> {
+    if class.enum_type().is_none() {
+        return Err(Error::new(
+            span,
+            format!("`{}` is not an enum", class.full_name()),
+        ));
+    }
+
+    match (flags, class.has_attribute("Flags")) {
+        (true, false) => {
+            return Err(Error::new(
+                span,
+                format!(
+                    "`{}` is not a [Flags] enum, remove `flags`",
+                    class.full_name()
+                ),
+            ))
+        }
+
+        (false, true) => {
+            return Err(Error::new(
+                span,
+                format!("`{}` is a [Flags] enum, add `flags`", class.full_name()),
+            ))
+        }
+
+        _ => {}
+    }
+
+    let members = members(class)?;
+
+    for member in declared {
+        match members.iter().find(|m| m.name == member.name) {
+            Some(m) if m.value == member.value => {}
+
+            Some(m) => {
+                return Err(Error::new(
+                    member.span,
+                    format!(
+                        "`{}` is {} in the dump, not {}",
+                        member.name, m.value, member.value
+                    ),
+                ))
+            }
+
+            None => {
+                let suggestions =
+                    suggest::similar(&member.name, members.iter().map(|m| m.name.as_str()));
+
+                return Err(Error::new(
+                    member.span,
+                    format!(
+                        "`{}` has no member `{}`{}",
+                        class.full_name(),
+                        member.name,
+                        suggest::did_you_mean(&suggestions)
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn members(class: &Class) -> syn::Result<Vec<Member>> {
+    class
+        .enum_values()
+        .into_iter()
+        .map(|(name, value)| {
+            let value = value.parse().map_err(|_| {
+                Error::new(
+                    Span::call_site(),
+                    format!(
+                        "`{}.{}` has a non-integer value {}",
+                        class.full_name(),
+                        name,
+                        value
+                    ),
+                )
+            })?;
+
+            Ok(Member {
+                name: name.to_string(),
+                value,
+                span: Span::call_site(),
+            })
+        })
+        .collect()
+}
+
+/// the member's name as a Rust identifier, if it can be one
+fn member_ident(name: &str) -> Option<Ident> {
+    match syn::parse_str::<Ident>(name) {
+        Ok(ident) => Some(ident),
+        Err(_) => syn::parse_str::<Ident>(&format!("r#{}", name)).ok(),
+    }
+}
+
+fn expand_enum(
+    item: &ItemEnum,
+    underlying: &Ident,
+    members: &[(Ident, &Member)],
+) -> syn::Result<TokenStream> {
+    let ItemEnum {
+        attrs, vis, ident, ..
+    } = item;
+
+    let other = format_ident!("{}", OTHER);
+
+    if let Some((ident, _)) = members.iter().find(|(ident, _)| *ident == other) {
+        return Err(Error::new(
+            ident.span(),
+            format!(
+                "a member named `{}` conflicts with the variant for unknown values",
+                OTHER
+            ),
+        ));
+    }
+
+    let variants = members.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
+    let values = members
+        .iter()
+        .map(|(_, m)| Literal::i128_unsuffixed(m.value))
+        .collect::<Vec<_>>();
+    let names = members.iter().map(|(_, m)| &m.name);
+
+    // members can share a value, in which case a value reads as the first
+    let mut seen = vec![];
+    let from_value = members
+        .iter()
+        .filter(|(_, m)| {
+            let first = !seen.contains(&m.value);
+            seen.push(m.value);
+            first
+        })
+        .map(|(ident, m)| {
+            let value = Literal::i128_unsuffixed(m.value);
+            quote! { #value => Self::#ident, }
+        });
+
+    Ok(quote! {
+        #( #attrs )*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #ident {
+            #( #variants, )*
+            /// a value that isn't one of the enum's members
+            #other(#underlying),
+        }
+
+        impl #ident {
+            pub fn from_value(value: #underlying) -> Self {
+                match value {
+                    #( #from_value )*
+                    value => Self::#other(value),
+                }
+            }
+
+            pub fn value(self) -> #underlying {
+                match self {
+                    #( Self::#variants => #values, )*
+                    Self::#other(value) => value,
+                }
+            }
+        }
+
+        impl crate::memory::MemoryRead for #ident {
+            const SIZE: usize = <#underlying as crate::memory::MemoryRead>::SIZE;
+            const ALIGN: usize = <#underlying as crate::memory::MemoryRead>::ALIGN;
+
+            fn read(view: &crate::memory::ProcessMemoryView, address: u64) -> crate::Result<Self> {
+                Ok(Self::from_value(view.read(address)?))
+            }
+        }
+
+        impl std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    #( Self::#variants => f.write_str(#names), )*
+                    Self::#other(value) => write!(f, "{}", value),
+                }
+            }
+        }
+    })
+}
+
+fn expand_flags(item: &ItemEnum, underlying: &Ident, members: &[(Ident, &Member)]) -> TokenStream {
+    let ItemEnum {
+        attrs, vis, ident, ..
+    } = item;
+
+    let flags = members.iter().map(|(ident, m)| {
+        let value = Literal::i128_unsuffixed(m.value);
+        quote! {
+            #[allow(non_upper_case_globals)]
+            const #ident = #value;
+        }
+    });
+
+    // like C#, the largest members are matched first so that combinations with their own names
+    // take their bits before the single flags in them do, and the matched names are then written
+    // from smallest to largest, followed by any bits left over; a value with no flags set is
+    // written as the member for 0, if there is one
+    let mut named = members
+        .iter()
+        .filter(|(_, m)| m.value != 0)
+        .collect::<Vec<_>>();
+    named.sort_by_key(|(_, m)| std::cmp::Reverse(m.value));
+
+    let named_flags = named.iter().map(|(ident, _)| ident);
+    let named_names = named.iter().map(|(_, m)| &m.name);
+
+    let empty = match members.iter().find(|(_, m)| m.value == 0) {
+        Some((_, m)) => Literal::string(&m.name),
+        None => Literal::string("0"),
+    };
+
+    quote! {
+        bitflags::bitflags! {
+            #( #attrs )*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #vis struct #ident: #underlying {
+                #( #flags )*
+
+                // flags added by later versions of the game are kept when read
+                const _ = !0;
+            }
+        }
+
+        impl crate::memory::MemoryRead for #ident {
+            const SIZE: usize = <#underlying as crate::memory::MemoryRead>::SIZE;
+            const ALIGN: usize = <#underlying as crate::memory::MemoryRead>::ALIGN;
+
+            fn read(view: &crate::memory::ProcessMemoryView, address: u64) -> crate::Result<Self> {
+                Ok(Self::from_bits_retain(view.read(address)?))
+            }
+        }
+
+        impl std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                if self.is_empty() {
+                    return f.write_str(#empty);
+                }
+
+                let mut remaining = *self;
+                let mut names = vec![];
+
+                let named: &[(Self, &str)] = &[ #( (Self::#named_flags, #named_names) ),* ];
+
+                for &(flag, name) in named {
+                    if remaining.contains(flag) {
+                        remaining.remove(flag);
+                        names.push(name.to_string());
+                    }
+                }
+
+                // matched largest first, written smallest first
+                names.reverse();
+
+                if !remaining.is_empty() {
+                    names.push(format!("{:#x}", remaining.bits()));
+                }
+
+                f.write_str(&names.join(", "))
+            }
+        }
+    }
+}
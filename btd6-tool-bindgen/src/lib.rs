@@ -1,140 +1,113 @@
-use core::panic;
-
-use anyhow::Context;
-use assert_matches::assert_matches;
+use btd6_tool_dump::{Class, Dump};
 use proc_macro::TokenStream;
-use proc_macro2::Literal;
+use proc_macro2::{Literal, Span};
 use quote::quote;
-use regex::Regex;
-use syn::{punctuated::Punctuated, Expr, Lit, Meta};
-
-type Result<T> = anyhow::Result<T>;
-
-const DUMP: &'static str = include_str!("../dump.cs");
-
-struct BindingGenerator {
-    dump: String,
-}
-
-impl BindingGenerator {
-    fn new(dump: String) -> Self {
-        Self { dump }
-    }
-
-    fn load() -> Self {
-        Self::new(DUMP.to_string())
-    }
-
-    fn get_class(&'_ self, namespace: &str, name: &str) -> Result<ClassBindingGenerator<'_>> {
-        let regex = Regex::new(&format!(
-            r"// Namespace: {}\n(\[.*\n)*.*class {} .*\n\{{\n((\n|\s+.+\n)*)\}}",
-            regex::escape(&namespace),
-            regex::escape(&name),
-        ))?;
-
-        let captures = regex.captures(&self.dump).context("class not found")?;
-        let body = captures.get(2).unwrap().as_str();
-
-        Ok(ClassBindingGenerator { body })
-    }
-}
-
-struct ClassBindingGenerator<'a> {
-    body: &'a str,
-}
-
-impl<'a> ClassBindingGenerator<'a> {
-    fn get_field_offset(&self, field_name: &str) -> Result<usize> {
-        let regex = Regex::new(&format!(
-            r"\s([^\s]+) {}; // 0x([A-Z\d]+)\n",
-            regex::escape(field_name),
-        ))?;
+use syn::{punctuated::Punctuated, Error, Expr, Lit, LitStr, Meta};
 
-        let captures = regex
-            .captures(&self.body)
-            .context(format!("field not found: {}", field_name))?;
-        let raw_value = captures.get(2).unwrap().as_str();
-
-        let value = usize::from_str_radix(raw_value, 16)?;
-
-        // offsets from il2cpp include the constant-sized class offset of 16 bytes
-        Ok(value - 16)
-    }
-}
+mod dumps;
+mod enumeration;
+mod suggest;
+mod typecheck;
 
 #[proc_macro_attribute]
 pub fn class(attr: TokenStream, item: TokenStream) -> TokenStream {
     let arguments =
         syn::parse_macro_input!(attr with Punctuated::<Meta, syn::Token![,]>::parse_terminated);
 
+    let item = syn::parse_macro_input!(item as syn::ItemStruct);
+
+    expand_class(&arguments, &item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_class(
+    arguments: &Punctuated<Meta, syn::Token![,]>,
+    item: &syn::ItemStruct,
+) -> syn::Result<proc_macro2::TokenStream> {
     let mut namespace = None;
     let mut base = None;
     let mut rename = None;
+    let mut immutable = false;
 
     for attr in arguments.iter() {
         match attr {
-            Meta::NameValue(value) => {
-                assert_eq!(value.path.segments.len(), 1);
-                match value.path.segments[0].ident.to_string().as_ref() {
-                    "namespace" => {
-                        let value = assert_matches!(&value.value, Expr::Lit(v) => v);
-                        let literal = assert_matches!(&value.lit, Lit::Str(v) => v);
-                        namespace = Some(literal.value());
-                    }
+            Meta::Path(path) if path.is_ident("immutable") => immutable = true,
 
-                    "base" => {
-                        let value = assert_matches!(&value.value, Expr::Path(v) => v);
-                        assert_eq!(value.path.segments.len(), 1);
-                        base = Some(&value.path.segments[0].ident);
-                    }
+            Meta::NameValue(value) if value.path.is_ident("namespace") => {
+                namespace = Some(string_literal(&value.value)?.value());
+            }
 
-                    "rename" => {
-                        let value = assert_matches!(&value.value, Expr::Lit(v) => v);
-                        let literal = assert_matches!(&value.lit, Lit::Str(v) => v);
-                        rename = Some(literal.value());
+            Meta::NameValue(value) if value.path.is_ident("base") => {
+                base = Some(match &value.value {
+                    Expr::Path(path) if path.path.get_ident().is_some() => {
+                        path.path.get_ident().unwrap()
                     }
+                    v => return Err(Error::new_spanned(v, "expected the name of a bound class")),
+                });
+            }
 
-                    v => panic!("unknown argument: {v}"),
-                }
+            Meta::NameValue(value) if value.path.is_ident("rename") => {
+                rename = Some(string_literal(&value.value)?);
             }
 
-            _ => panic!("invalid arguments"),
+            _ => {
+                return Err(Error::new_spanned(
+                    attr,
+                    "unknown argument, expected `namespace`, `base`, `rename` or `immutable`",
+                ))
+            }
         }
     }
 
-    let namespace = namespace.expect("Namespace not specified");
+    let Some(namespace) = namespace else {
+        return Err(Error::new(
+            Span::call_site(),
+            "missing argument `namespace = \"...\"`",
+        ));
+    };
 
-    let item = syn::parse_macro_input!(item as syn::ItemStruct);
+    let name = &item.ident;
 
-    let name = item.ident;
+    let csharp_full_name = rename.map_or(name.to_string(), LitStr::value);
+    let csharp_base_name = csharp_full_name.split('.').next_back().unwrap();
 
-    let csharp_full_name = rename.unwrap_or(name.to_string());
-    let csharp_base_name = csharp_full_name
-        .split(".")
-        .last()
-        .expect("invalid identifier");
+    let class_span = rename.map_or(name.span(), LitStr::span);
 
-    let bindgen = BindingGenerator::load();
-    let class = bindgen.get_class(&namespace, &csharp_full_name).unwrap();
+    let dumps = dumps::dumps()?;
 
-    let fields = item.fields.iter().map(|field| {
-        let name = field.ident.as_ref().unwrap();
-        let ty = &field.ty;
+    if let Some(newest) = dumps.first() {
+        let found = dumps
+            .iter()
+            .any(|d| d.dump.get_class(&namespace, &csharp_full_name).is_ok());
 
-        let rename_arg = assert_matches!(&field.attrs[0].meta, Meta::NameValue(v) => v);
+        if !found {
+            return Err(class_error(
+                &newest.dump,
+                &namespace,
+                &csharp_full_name,
+                class_span,
+            ));
+        }
+    }
 
-        assert_eq!("rename", rename_arg.path.segments[0].ident.to_string());
-        let rename_value = assert_matches!(&rename_arg.value, Expr::Lit(v) => v);
-        let rename_value = assert_matches!(&rename_value.lit, Lit::Str(v) => v);
-        let rename = rename_value.value();
+    let mut errors = Vec::new();
+    let mut fields = Vec::new();
 
-        let offset = class.get_field_offset(&rename).unwrap();
-        let offset = Literal::usize_unsuffixed(offset);
+    for field in item.fields.iter() {
+        match expand_field(dumps, &namespace, &csharp_full_name, field) {
+            Ok(field) => fields.push(field),
+            Err(e) => errors.push(e),
+        }
+    }
 
-        quote! {
-            field!(#offset #name: #ty);
+    if let Some(mut error) = errors.pop() {
+        for e in errors {
+            error.combine(e);
         }
-    });
+
+        return Err(error);
+    }
 
     let inheritence = base.map(|base| {
         quote! {
@@ -150,14 +123,302 @@ pub fn class(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let csharp_name = Literal::string(csharp_base_name);
 
-    let output: proc_macro2::TokenStream = quote! {
-        object_type!(#name ; #csharp_name);
+    let object_type = match immutable {
+        true => quote! { object_type!(#name ; #csharp_name ; immutable); },
+        false => quote! { object_type!(#name ; #csharp_name); },
+    };
+
+    Ok(quote! {
+        #object_type
 
         impl #name {
             #( #fields )*
         }
 
         #inheritence
+    })
+}
+
+fn expand_field(
+    dumps: &[dumps::VersionDump],
+    namespace: &str,
+    class_name: &str,
+    field: &syn::Field,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let Some(name) = &field.ident else {
+        return Err(Error::new_spanned(field, "expected a named field"));
+    };
+
+    let ty = &field.ty;
+
+    let rename = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("rename"))
+        .ok_or_else(|| {
+            Error::new_spanned(name, "missing `#[rename = \"...\"]` naming the C# field")
+        })?;
+
+    let rename = match &rename.meta {
+        Meta::NameValue(value) => string_literal(&value.value)?,
+        meta => return Err(Error::new_spanned(meta, "expected `#[rename = \"...\"]`")),
+    };
+
+    let csharp_name = rename.value();
+
+    // the offset in each version the field exists in, which must be at least one if there are
+    // any dumps at all
+    let mut versions = vec![];
+    let mut offsets = vec![];
+
+    for d in dumps {
+        let Ok(class) = d.dump.get_class(namespace, class_name) else {
+            continue;
+        };
+
+        let (Ok(offset), Some(csharp_field)) = (
+            class.get_field_offset(&csharp_name),
+            class.get_field(&csharp_name),
+        ) else {
+            continue;
+        };
+
+        typecheck::check(&d.dump, class, &csharp_field.type_name, ty).map_err(|message| {
+            Error::new_spanned(ty, format!("{} (in the dump of {})", message, d.version))
+        })?;
+
+        versions.push(d.version.as_str());
+        offsets.push(Literal::usize_unsuffixed(offset));
+    }
+
+    if versions.is_empty() {
+        let newest = dumps.iter().find_map(|d| {
+            let class = d.dump.get_class(namespace, class_name).ok()?;
+            Some((&d.dump, class))
+        });
+
+        if let Some((dump, class)) = newest {
+            return Err(field_error(dump, class, &csharp_name, rename.span()));
+        }
+    }
+
+    Ok(quote! {
+        field!(#name: #ty ; #namespace, #class_name, #csharp_name ; &[ #( (#versions, #offsets) ),* ]);
+    })
+}
+
+fn string_literal(expr: &Expr) -> syn::Result<&LitStr> {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(literal),
+            ..
+        }) => Ok(literal),
+
+        expr => Err(Error::new_spanned(expr, "expected a string literal")),
+    }
+}
+
+/// why a class isn't in the dump, suggesting similarly named classes in its namespace or classes
+/// of the same name in other namespaces
+fn class_error(dump: &Dump, namespace: &str, name: &str, span: Span) -> Error {
+    if let Err(e) = dump.get_class(namespace, name) {
+        if !dump.find_classes(namespace, name).is_empty() {
+            return Error::new(span, format!("{:#}", e));
+        }
+    }
+
+    let elsewhere = dump
+        .classes()
+        .filter(|c| c.name == name)
+        .map(|c| c.namespace.as_str())
+        .collect::<Vec<_>>();
+
+    let hint = match elsewhere.as_slice() {
+        [] => suggest::did_you_mean(&suggest::similar(
+            name,
+            dump.classes()
+                .filter(|c| c.namespace == namespace)
+                .map(|c| c.name.as_str()),
+        )),
+
+        namespaces => format!(
+            " (it is in namespace {})",
+            namespaces
+                .iter()
+                .map(|n| format!("`{}`", n))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    Error::new(
+        span,
+        format!(
+            "class `{}` not found in namespace `{}`{}",
+            name, namespace, hint
+        ),
+    )
+}
+
+/// why a field can't be bound, suggesting similarly named fields of the class and its bases
+fn field_error(dump: &Dump, class: &Class, field: &str, span: Span) -> Error {
+    let mut classes = vec![class];
+    while let Some(base) = dump.get_base(classes.last().unwrap()) {
+        if classes.len() > 32 || classes.iter().any(|c| std::ptr::eq(*c, base)) {
+            break;
+        }
+
+        classes.push(base);
+    }
+
+    if let Some(found) = class.get_field(field) {
+        let message = match (found.is_const(), found.is_static()) {
+            (true, _) => format!("`{}` is a constant, not an instance field", field),
+            (_, true) => format!("`{}` is a static field, not an instance field", field),
+            _ => format!("field `{}` has no offset in the dump", field),
+        };
+
+        return Error::new(span, message);
+    }
+
+    if let Some(base) = classes[1..].iter().find(|c| c.get_field(field).is_some()) {
+        return Error::new(
+            span,
+            format!(
+                "field `{}` is declared by base class `{}`, bind it there",
+                field,
+                base.full_name()
+            ),
+        );
+    }
+
+    let backing_field = format!("<{}>k__BackingField", field);
+    let suggestions = match class.get_field(&backing_field) {
+        Some(_) => vec![backing_field.as_str()],
+        None => suggest::similar(
+            field,
+            classes
+                .iter()
+                .flat_map(|c| c.instance_fields())
+                .map(|f| f.name.as_str()),
+        ),
+    };
+
+    Error::new(
+        span,
+        format!(
+            "field `{}` not found in `{}`{}",
+            field,
+            class.full_name(),
+            suggest::did_you_mean(&suggestions)
+        ),
+    )
+}
+
+/// generates a Rust enum, or bitflags if `flags` is given, from the members of a C# enum. Members
+/// written in the Rust enum are checked against the dump and used when there isn't one
+#[proc_macro_attribute]
+pub fn enumeration(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let arguments =
+        syn::parse_macro_input!(attr with Punctuated::<Meta, syn::Token![,]>::parse_terminated);
+
+    let item = syn::parse_macro_input!(item as syn::ItemEnum);
+
+    enumeration::expand(&arguments, &item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// the game version of each build id with a dump, as `&[(build_id, version)]`
+#[proc_macro]
+pub fn game_builds(item: TokenStream) -> TokenStream {
+    syn::parse_macro_input!(item as syn::parse::Nothing);
+
+    let builds = match dumps::builds() {
+        Ok(builds) => builds,
+        Err(e) => return e.into_compile_error().into(),
+    };
+
+    let (build_ids, versions): (Vec<_>, Vec<_>) = builds.into_iter().unzip();
+
+    let output: proc_macro2::TokenStream = quote! {
+        &[ #( (#build_ids, #versions) ),* ]
+    };
+
+    output.into()
+}
+
+/// implements `MemoryRead` for a value type, laying out its fields in order like C#'s sequential
+/// layout, with each field aligned to its own alignment
+#[proc_macro_derive(MemoryRead)]
+pub fn derive_memory_read(item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item as syn::ItemStruct);
+
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    let types = item
+        .fields
+        .iter()
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+
+    let layout = quote! {
+        crate::memory::sequential_layout([
+            #( (<#types as crate::memory::MemoryRead>::SIZE, <#types as crate::memory::MemoryRead>::ALIGN) ),*
+        ])
+    };
+
+    let reads = types.iter().enumerate().map(|(i, ty)| {
+        quote! { view.read::<#ty>(address + offsets[#i] as u64)? }
+    });
+
+    let value = match &item.fields {
+        syn::Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote! { Self { #( #names: #reads ),* } }
+        }
+
+        syn::Fields::Unnamed(_) => quote! { Self( #( #reads ),* ) },
+
+        syn::Fields::Unit => quote! { Self },
+    };
+
+    let rebound = match &item.fields {
+        syn::Fields::Named(fields) => {
+            let names = fields
+                .named
+                .iter()
+                .map(|field| &field.ident)
+                .collect::<Vec<_>>();
+            quote! { Self { #( #names: crate::memory::MemoryRead::rebind(self.#names, memory) ),* } }
+        }
+
+        syn::Fields::Unnamed(_) => {
+            let indices = (0..types.len()).map(syn::Index::from);
+            quote! { Self( #( crate::memory::MemoryRead::rebind(self.#indices, memory) ),* ) }
+        }
+
+        syn::Fields::Unit => quote! { self },
+    };
+
+    let output: proc_macro2::TokenStream = quote! {
+        impl #impl_generics crate::memory::MemoryRead for #name #ty_generics #where_clause {
+            const SIZE: usize = #layout.1;
+            const ALIGN: usize = #layout.2;
+
+            #[allow(unused_variables)]
+            fn read(view: &crate::memory::ProcessMemoryView, address: u64) -> crate::Result<Self> {
+                let (offsets, _, _) = #layout;
+
+                Ok(#value)
+            }
+
+            #[allow(unused_variables)]
+            fn rebind(self, memory: &crate::memory::ProcessMemoryView) -> Self {
+                #rebound
+            }
+        }
     };
 
     output.into()
@@ -0,0 +1,62 @@
+/// the candidates most similar to a misspelled name, best first, including any that contain it
+pub fn similar<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(2);
+
+    let mut matches = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let lowercase = candidate.to_lowercase();
+
+            let distance = match distance(&name, &lowercase) {
+                d if d <= max_distance => d,
+                _ if name.len() >= 3 && lowercase.contains(&name) => max_distance + 1,
+                _ => return None,
+            };
+
+            Some((distance, candidate))
+        })
+        .collect::<Vec<_>>();
+
+    matches.sort();
+    matches.dedup_by_key(|&mut (_, candidate)| candidate);
+
+    matches
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// ` (did you mean `a` or `b`?)`, or nothing if there are no suggestions
+pub fn did_you_mean(suggestions: &[&str]) -> String {
+    let quoted = suggestions
+        .iter()
+        .map(|s| format!("`{}`", s))
+        .collect::<Vec<_>>();
+
+    match quoted.split_last() {
+        None => String::new(),
+        Some((last, [])) => format!(" (did you mean {}?)", last),
+        Some((last, rest)) => format!(" (did you mean {} or {}?)", rest.join(", "), last),
+    }
+}
+
+/// the edit distance between two strings
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + (ca != cb) as usize;
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
@@ -0,0 +1,452 @@
+use btd6_tool_dump::{Class, ClassKind, Dump};
+use quote::ToTokens;
+use syn::{GenericArgument, PathArguments, Type};
+
+/// the Rust types each C# primitive can be read as, with integers keeping their size and sign
+const PRIMITIVES: &[(&str, &[&str])] = &[
+    ("bool", &["bool"]),
+    ("byte", &["u8"]),
+    ("sbyte", &["i8"]),
+    ("short", &["i16"]),
+    ("ushort", &["u16"]),
+    ("char", &["u16"]),
+    ("int", &["i32"]),
+    ("uint", &["u32"]),
+    ("long", &["i64"]),
+    ("ulong", &["u64"]),
+    ("float", &["f32"]),
+    ("double", &["f64"]),
+    ("IntPtr", &["i64", "Pointer"]),
+    ("UIntPtr", &["u64", "Pointer"]),
+];
+
+/// the Rust type bound to each C# collection, with the same type arguments
+const COLLECTIONS: &[&str] = &[
+    "List",
+    "Dictionary",
+    "HashSet",
+    "Queue",
+    "Stack",
+    "LinkedList",
+    "LinkedListNode",
+    "LockList",
+];
+
+/// Rust types that can hold any reference
+const REFERENCES: &[&str] = &["Object", "Pointer"];
+
+/// the most bases followed from a class, in case a dump names them in a cycle
+const MAX_BASES: usize = 32;
+
+const RUST_PRIMITIVES: &[&str] = &[
+    "bool", "u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64", "f32", "f64", "usize", "isize",
+];
+
+/// The C# type of a bound field, resolved against the dump it was declared in
+struct Checker<'a> {
+    dump: &'a Dump,
+    class: &'a Class,
+}
+
+/// checks that a Rust type reads a field of a C# type correctly, explaining why not if it doesn't
+pub fn check(dump: &Dump, class: &Class, csharp: &str, rust: &Type) -> Result<(), String> {
+    Checker { dump, class }.check(csharp, rust)
+}
+
+impl Checker<'_> {
+    fn check(&self, csharp: &str, rust: &Type) -> Result<(), String> {
+        let csharp = csharp.trim();
+
+        let Some((rust_name, rust_arguments)) = split_rust_type(rust) else {
+            return Err(self.mismatch(csharp, rust, None));
+        };
+
+        // `T` in a generic class can be bound to anything
+        if self.class.generic_parameters.iter().any(|p| p == csharp) {
+            return Ok(());
+        }
+
+        if let Some((_, allowed)) = PRIMITIVES.iter().find(|(name, _)| *name == csharp) {
+            return match allowed.contains(&rust_name.as_str()) {
+                true => Ok(()),
+                false => Err(self.mismatch(csharp, rust, Some(allowed.join("` or `")))),
+            };
+        }
+
+        let kind = self.kind_of(csharp);
+
+        match rust_name.as_str() {
+            "Option" => match (kind, rust_arguments.as_slice()) {
+                (Some(ClassKind::Struct) | Some(ClassKind::Enum), _) => Err(format!(
+                    "`{}` is a value type, so it can't be null and shouldn't be read as `{}`",
+                    csharp,
+                    display(rust)
+                )),
+
+                (_, [inner]) => self.check(csharp, inner),
+                _ => Err(self.mismatch(csharp, rust, None)),
+            },
+
+            _ if RUST_PRIMITIVES.contains(&rust_name.as_str()) => match kind {
+                Some(ClassKind::Enum) => self.check_enum(csharp, rust),
+                _ => Err(self.mismatch(csharp, rust, None)),
+            },
+
+            "CSharpString" => match csharp {
+                "string" => Ok(()),
+                _ => Err(self.mismatch(csharp, rust, None)),
+            },
+
+            _ if csharp == "string" => {
+                Err(self.mismatch(csharp, rust, Some("CSharpString".into())))
+            }
+
+            "Array" => match (csharp.strip_suffix("[]"), rust_arguments.as_slice()) {
+                (Some(element), [rust_element]) => self.check(element, rust_element),
+                _ => Err(self.mismatch(csharp, rust, None)),
+            },
+
+            _ if csharp.ends_with("[]") => {
+                let expected = format!("Array<_>` or `{}", REFERENCES.join("` or `"));
+                Err(self.mismatch(csharp, rust, Some(expected)))
+            }
+
+            name if COLLECTIONS.contains(&name) => {
+                let (csharp_name, csharp_arguments) = split_csharp_type(csharp);
+
+                if csharp_name != name || csharp_arguments.len() != rust_arguments.len() {
+                    return Err(self.mismatch(csharp, rust, None));
+                }
+
+                csharp_arguments
+                    .iter()
+                    .zip(&rust_arguments)
+                    .try_for_each(|(csharp, rust)| self.check(csharp, rust))
+            }
+
+            name if REFERENCES.contains(&name) => match kind {
+                Some(ClassKind::Struct) | Some(ClassKind::Enum) => {
+                    Err(self.mismatch(csharp, rust, None))
+                }
+                _ => Ok(()),
+            },
+
+            // a bound class or value type, which must have the same name, or for a class be one of
+            // its bases or a class derived from it
+            name => {
+                let (csharp_name, _) = split_csharp_type(csharp);
+                let csharp_name = rust_name_of(csharp_name);
+
+                match kind {
+                    _ if COLLECTIONS.contains(&csharp_name.as_str()) => {
+                        Err(self.mismatch(csharp, rust, Some(format!("{}<_>", csharp_name))))
+                    }
+
+                    _ if csharp_name == name => Ok(()),
+
+                    // every class derives from object
+                    Some(ClassKind::Class) if csharp == "object" => Ok(()),
+
+                    Some(ClassKind::Class) | Some(ClassKind::Interface)
+                        if self.is_related(csharp, name) =>
+                    {
+                        Ok(())
+                    }
+
+                    Some(ClassKind::Struct) | Some(ClassKind::Enum) => {
+                        Err(self.mismatch(csharp, rust, Some(csharp_name)))
+                    }
+
+                    _ => {
+                        let expected =
+                            format!("{}` or `{}", csharp_name, REFERENCES.join("` or `"));
+                        Err(self.mismatch(csharp, rust, Some(expected)))
+                    }
+                }
+            }
+        }
+    }
+
+    /// whether the Rust binding `rust_name` is one of the C# class's bases, or derives from it
+    fn is_related(&self, csharp: &str, rust_name: &str) -> bool {
+        let Some(class) = self.find_class(csharp) else {
+            return false;
+        };
+
+        if self
+            .ancestors(class)
+            .any(|c| rust_name_of(&c.name) == rust_name)
+        {
+            return true;
+        }
+
+        self.dump
+            .classes()
+            .filter(|c| rust_name_of(&c.name) == rust_name)
+            .any(|derived| {
+                self.ancestors(derived).any(|c| {
+                    std::ptr::eq(c, class)
+                        || c.interfaces
+                            .iter()
+                            .any(|i| split_csharp_type(i).0 == class.name)
+                })
+            })
+    }
+
+    /// a class followed by each of its bases, stopping at any cycle
+    fn ancestors<'b>(&'b self, class: &'b Class) -> impl Iterator<Item = &'b Class> + 'b {
+        let mut seen = vec![];
+
+        std::iter::successors(Some(class), |c| self.dump.get_base(c))
+            .take_while(move |c| {
+                let new = !seen.iter().any(|s| std::ptr::eq(*s, *c));
+                seen.push(*c);
+                new
+            })
+            .take(MAX_BASES)
+    }
+
+    /// an enum is read as its underlying integer type
+    fn check_enum(&self, csharp: &str, rust: &Type) -> Result<(), String> {
+        let underlying = self
+            .find_class(csharp)
+            .and_then(|c| c.enum_type())
+            .unwrap_or("int");
+
+        self.check(underlying, rust)
+            .map_err(|_| self.mismatch(csharp, rust, None))
+    }
+
+    /// the kind of a named C# type, if the dump has it
+    fn kind_of(&self, csharp: &str) -> Option<ClassKind> {
+        if csharp == "object" || csharp.ends_with("[]") {
+            return Some(ClassKind::Class);
+        }
+
+        self.find_class(csharp).map(|c| c.kind)
+    }
+
+    /// a class by the name it's referred to in a field, preferring one in the binding's namespace
+    fn find_class(&self, csharp: &str) -> Option<&Class> {
+        let (name, _) = split_csharp_type(csharp);
+
+        self.dump
+            .find_classes(&self.class.namespace, name)
+            .into_iter()
+            .chain(self.dump.classes().filter(|c| c.name == name))
+            .next()
+    }
+
+    fn mismatch(&self, csharp: &str, rust: &Type, expected: Option<String>) -> String {
+        let expected = match expected {
+            Some(expected) => format!(", expected `{}`", expected),
+            None => String::new(),
+        };
+
+        format!(
+            "field of C# type `{}` can't be read as `{}`{}",
+            csharp,
+            display(rust),
+            expected
+        )
+    }
+}
+
+/// a Rust type as it would be written, rather than spaced out like a token stream
+fn display(rust: &Type) -> String {
+    rust.to_token_stream()
+        .to_string()
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+}
+
+/// the name a C# class is bound as in Rust, e.g. `PlayerContext_Context` for a nested class
+fn rust_name_of(csharp_name: &str) -> String {
+    csharp_name.replace('.', "_")
+}
+
+/// `Dictionary<int, List<Tower>>` to `Dictionary` and `["int", "List<Tower>"]`
+fn split_csharp_type(csharp: &str) -> (&str, Vec<&str>) {
+    let Some((name, arguments)) = csharp.split_once('<') else {
+        return (csharp, vec![]);
+    };
+
+    let arguments = arguments.strip_suffix('>').unwrap_or(arguments);
+
+    let mut depth = 0;
+    let mut start = 0;
+    let mut split = vec![];
+
+    for (i, c) in arguments.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                split.push(arguments[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    split.push(arguments[start..].trim());
+
+    (name, split)
+}
+
+/// the last segment of a Rust path type and its type arguments
+fn split_rust_type(rust: &Type) -> Option<(String, Vec<Type>)> {
+    let Type::Path(path) = rust else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+
+    let arguments = match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            })
+            .collect(),
+
+        _ => vec![],
+    };
+
+    Some((segment.ident.to_string(), arguments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = r#"
+// Namespace: Assets.Scripts.Models
+public class Model // TypeDefIndex: 0
+{
+	// Fields
+	private string _name; // 0x10
+}
+
+// Namespace: Assets.Scripts.Models.Towers
+public class TowerModel : Model, ITowerModel // TypeDefIndex: 1
+{
+	// Fields
+	public Model parent; // 0x18
+	public TowerModel upgraded; // 0x20
+	public ITowerModel source; // 0x28
+	public object tag; // 0x30
+	public int tier; // 0x38
+	public ObjectId id; // 0x3C
+	public PlayerContext.Context context; // 0x40
+	public List<TowerModel> children; // 0x48
+	public TowerSet towerSet; // 0x50
+	public IntPtr handle; // 0x58
+}
+
+// Namespace: Assets.Scripts.Models.Towers
+public interface ITowerModel // TypeDefIndex: 2
+{
+}
+
+// Namespace: Assets.Scripts.Models.Bloons
+public class BloonModel : Model // TypeDefIndex: 3
+{
+}
+
+// Namespace: Assets.Scripts.Simulation.Objects
+public struct ObjectId // TypeDefIndex: 4
+{
+	// Fields
+	public uint data; // 0x0
+}
+
+// Namespace: 
+public class PlayerContext.Context // TypeDefIndex: 5
+{
+}
+
+// Namespace: Assets.Scripts.Models.TowerSets
+[Flags]
+public enum TowerSet // TypeDefIndex: 6
+{
+	// Fields
+	public byte value__; // 0x0
+	public const TowerSet Hero = 16;
+}
+"#;
+
+    /// checks reading a field of TowerModel as a Rust type
+    fn check_field(field: &str, rust: &str) -> Result<(), String> {
+        let dump = Dump::parse(DUMP).unwrap();
+        let class = dump
+            .get_class("Assets.Scripts.Models.Towers", "TowerModel")
+            .unwrap();
+        let csharp = &class.get_field(field).unwrap().type_name;
+
+        check(&dump, class, csharp, &syn::parse_str(rust).unwrap())
+    }
+
+    #[test]
+    fn accepts_related_classes() {
+        // the same class, a base class, a derived class and any reference
+        assert_eq!(check_field("upgraded", "TowerModel"), Ok(()));
+        assert_eq!(check_field("upgraded", "Model"), Ok(()));
+        assert_eq!(check_field("parent", "TowerModel"), Ok(()));
+        assert_eq!(check_field("parent", "Option<Object>"), Ok(()));
+        assert_eq!(check_field("parent", "Pointer"), Ok(()));
+
+        assert_eq!(check_field("source", "TowerModel"), Ok(()));
+        assert_eq!(check_field("tag", "BloonModel"), Ok(()));
+        assert_eq!(check_field("context", "PlayerContext_Context"), Ok(()));
+        assert_eq!(check_field("children", "List<Model>"), Ok(()));
+        assert_eq!(check_field("id", "ObjectId"), Ok(()));
+    }
+
+    #[test]
+    fn reads_integers_with_their_size_and_sign() {
+        assert_eq!(check_field("tier", "i32"), Ok(()));
+        assert_eq!(
+            check_field("tier", "u32"),
+            Err("field of C# type `int` can't be read as `u32`, expected `i32`".into())
+        );
+        assert!(check_field("tier", "i64").is_err());
+
+        // enums are read as their underlying type, or as their binding
+        assert_eq!(check_field("towerSet", "u8"), Ok(()));
+        assert_eq!(check_field("towerSet", "TowerSet"), Ok(()));
+        assert_eq!(
+            check_field("towerSet", "i8"),
+            Err("field of C# type `TowerSet` can't be read as `i8`".into())
+        );
+        assert!(check_field("towerSet", "i32").is_err());
+
+        assert_eq!(check_field("handle", "i64"), Ok(()));
+        assert_eq!(check_field("handle", "Pointer"), Ok(()));
+        assert!(check_field("handle", "u64").is_err());
+    }
+
+    #[test]
+    fn rejects_unrelated_types() {
+        assert_eq!(
+            check_field("parent", "Tower"),
+            Err("field of C# type `Model` can't be read as `Tower`, expected `Model` or `Object` or `Pointer`".into())
+        );
+        assert!(check_field("upgraded", "BloonModel").is_err());
+        assert!(check_field("source", "BloonModel").is_err());
+        assert!(check_field("children", "List<BloonModel>").is_err());
+        assert!(check_field("children", "Array<TowerModel>").is_err());
+
+        assert_eq!(
+            check_field("id", "Object"),
+            Err("field of C# type `ObjectId` can't be read as `Object`".into())
+        );
+        assert!(check_field("id", "Option<ObjectId>").is_err());
+        assert!(check_field("tier", "f32").is_err());
+        assert!(check_field("parent", "u64").is_err());
+    }
+}
@@ -0,0 +1,11 @@
+/// the errors the macros give for bindings that don't match the dumps in `tests/dumps`
+#[test]
+fn compile_fail() {
+    std::env::set_var(
+        "BTD6_DUMPS",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/dumps"),
+    );
+
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
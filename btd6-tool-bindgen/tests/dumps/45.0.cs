@@ -0,0 +1,47 @@
+// Image 0: Assembly-CSharp.dll - 0
+
+// Namespace: Assets.Scripts.Simulation.Towers
+public class Tower : TowerBehavior // TypeDefIndex: 0
+{
+	// Fields
+	public string uniqueId; // 0x18
+	public float worth; // 0x20
+	public double damageDealt; // 0x28
+	public static int towerCount; // 0x0
+}
+
+// Namespace: Assets.Scripts.Simulation.Towers
+public class TowerBehavior // TypeDefIndex: 1
+{
+	// Fields
+	public Tower tower; // 0x10
+}
+
+// Namespace: Assets.Scripts.Models.TowerSets
+[Flags]
+public enum TowerSet // TypeDefIndex: 2
+{
+	// Fields
+	public byte value__; // 0x0
+	public const TowerSet None = 0;
+	public const TowerSet Primary = 1;
+	public const TowerSet Military = 2;
+	public const TowerSet Magic = 4;
+	public const TowerSet Support = 8;
+	public const TowerSet Hero = 16;
+	public const TowerSet Paragon = 32;
+	public const TowerSet Basic = 15;
+}
+
+// Namespace: Assets.Scripts.Models.Towers
+public enum TargetType // TypeDefIndex: 3
+{
+	// Fields
+	public int value__; // 0x0
+	public const TargetType Unknown = -1;
+	public const TargetType First = 0;
+	public const TargetType Last = 1;
+	public const TargetType Close = 2;
+	public const TargetType Strong = 3;
+	public const TargetType Default = 0;
+}
@@ -0,0 +1,11 @@
+/// the code the macros generate for the dumps in `tests/dumps`
+#[test]
+fn expand() {
+    std::env::set_var(
+        "BTD6_DUMPS",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/dumps"),
+    );
+
+    let t = trybuild::TestCases::new();
+    t.pass("tests/pass/*.rs");
+}
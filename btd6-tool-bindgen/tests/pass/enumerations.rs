@@ -0,0 +1,88 @@
+mod memory {
+    /// reads values from a byte buffer, standing in for btd6-tool's memory reader
+    pub struct ProcessMemoryView(pub Vec<u8>);
+
+    impl ProcessMemoryView {
+        pub fn read<T: MemoryRead>(&self, address: u64) -> crate::Result<T> {
+            T::read(self, address)
+        }
+    }
+
+    pub trait MemoryRead: Sized {
+        const SIZE: usize;
+        const ALIGN: usize;
+
+        fn read(view: &ProcessMemoryView, address: u64) -> crate::Result<Self>;
+    }
+
+    impl MemoryRead for u8 {
+        const SIZE: usize = 1;
+        const ALIGN: usize = 1;
+
+        fn read(view: &ProcessMemoryView, address: u64) -> crate::Result<Self> {
+            Ok(view.0[address as usize])
+        }
+    }
+
+    impl MemoryRead for i32 {
+        const SIZE: usize = 4;
+        const ALIGN: usize = 4;
+
+        fn read(view: &ProcessMemoryView, address: u64) -> crate::Result<Self> {
+            let bytes = &view.0[address as usize..address as usize + 4];
+            Ok(i32::from_le_bytes(bytes.try_into().unwrap()))
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, String>;
+
+use memory::{MemoryRead, ProcessMemoryView};
+
+#[btd6_tool_bindgen::enumeration(namespace = "Assets.Scripts.Models.TowerSets", flags)]
+pub enum TowerSet {
+    Hero = 16,
+}
+
+#[btd6_tool_bindgen::enumeration(namespace = "Assets.Scripts.Models.Towers")]
+pub enum TargetType {
+    Close = 2,
+}
+
+fn main() {
+    // flags take their members and underlying type from the dump
+    assert_eq!(TowerSet::SIZE, 1);
+    assert_eq!(TowerSet::Paragon.bits(), 32);
+    assert_eq!(TowerSet::Hero.to_string(), "Hero");
+    assert_eq!(TowerSet::None.to_string(), "None");
+    assert_eq!((TowerSet::Magic | TowerSet::Hero).to_string(), "Magic, Hero");
+
+    // named combinations take their flags' place, names are written smallest first, and unknown
+    // bits are kept after them
+    assert_eq!(TowerSet::from_bits_retain(0x4f).to_string(), "Basic, 0x40");
+    assert_eq!(
+        (TowerSet::Paragon | TowerSet::Basic | TowerSet::Hero).to_string(),
+        "Basic, Hero, Paragon"
+    );
+    assert_eq!(
+        (TowerSet::Magic | TowerSet::Paragon | TowerSet::from_bits_retain(0x80)).to_string(),
+        "Magic, Paragon, 0x80"
+    );
+
+    let view = ProcessMemoryView(vec![0x11, 0xff, 0xff, 0xff, 0xff, 7, 0, 0, 0]);
+    assert_eq!(
+        view.read::<TowerSet>(0).unwrap(),
+        TowerSet::Primary | TowerSet::Hero
+    );
+
+    // members sharing a value read as the first of them
+    assert_eq!(TargetType::SIZE, 4);
+    assert_eq!(TargetType::from_value(0), TargetType::First);
+    assert_eq!(TargetType::Default.value(), 0);
+    assert_eq!(TargetType::Unknown.to_string(), "Unknown");
+
+    // values that aren't members are kept too
+    assert_eq!(view.read::<TargetType>(1).unwrap(), TargetType::Unknown);
+    assert_eq!(view.read::<TargetType>(5).unwrap(), TargetType::Other(7));
+    assert_eq!(TargetType::Other(7).to_string(), "7");
+}
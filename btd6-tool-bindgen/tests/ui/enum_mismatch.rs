@@ -0,0 +1,11 @@
+#[btd6_tool_bindgen::enumeration(namespace = "Assets.Scripts.Models.TowerSets")]
+pub enum TowerSet {
+    Hero = 16,
+}
+
+#[btd6_tool_bindgen::enumeration(namespace = "Assets.Scripts.Models.Towers")]
+pub enum TargetType {
+    Close = 3,
+}
+
+fn main() {}
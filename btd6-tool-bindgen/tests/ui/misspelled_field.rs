@@ -0,0 +1,7 @@
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Simulation.Towers")]
+pub struct Tower {
+    #[rename = "wroth"]
+    worth: f32,
+}
+
+fn main() {}
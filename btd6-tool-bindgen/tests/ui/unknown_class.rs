@@ -0,0 +1,4 @@
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Simulation.Towers")]
+pub struct Towr {}
+
+fn main() {}
@@ -0,0 +1,816 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context};
+
+use crate::{
+    full_name,
+    lexer::{Lexer, Token},
+    ClassLayouts, Result,
+};
+
+/// modifiers that can precede a declaration's type or name
+const MODIFIERS: &[&str] = &[
+    "public",
+    "private",
+    "protected",
+    "internal",
+    "static",
+    "readonly",
+    "const",
+    "volatile",
+    "abstract",
+    "sealed",
+    "virtual",
+    "override",
+    "extern",
+    "unsafe",
+    "fixed",
+    "new",
+    "ref",
+    "out",
+    "in",
+    "params",
+    "this",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassKind {
+    Class,
+    Struct,
+    Enum,
+    Interface,
+}
+
+/// A class, struct, enum or interface in Il2CppDumper's dump.cs
+#[derive(Debug, Clone)]
+pub struct Class {
+    pub namespace: String,
+    /// the name including any declaring types, e.g. `PlayerContext.Context`, without generic parameters
+    pub name: String,
+    pub kind: ClassKind,
+    pub generic_parameters: Vec<String>,
+    /// the base class, or the underlying type of an enum, as dump.cs names it
+    pub base: Option<String>,
+    pub interfaces: Vec<String>,
+    pub type_index: Option<u32>,
+    /// the assembly defining the class, if dump.cs lists its images
+    pub image: Option<String>,
+    /// the names of the attributes on the declaration, without any arguments, e.g. `Flags`
+    pub attributes: Vec<String>,
+    pub fields: Vec<Field>,
+    pub methods: Vec<Method>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub type_name: String,
+    pub modifiers: Vec<String>,
+    /// the offset il2cpp gives the field, including the object header, or into the class statics
+    pub offset: Option<usize>,
+    /// the value of a constant, e.g. an enum member
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Method {
+    pub name: String,
+    pub return_type: String,
+    pub modifiers: Vec<String>,
+    pub parameters: Vec<Parameter>,
+    /// the method's address relative to the start of GameAssembly.dll, if it has a body
+    pub rva: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub name: String,
+    pub type_name: String,
+}
+
+/// An assembly listed at the top of dump.cs, whose types start at `first_type`
+#[derive(Debug, Clone)]
+struct Image {
+    name: String,
+    first_type: u32,
+}
+
+/// The classes in Il2CppDumper's dump.cs, indexed by namespace and name
+#[derive(Debug, Default)]
+pub struct Dump {
+    classes: Vec<Class>,
+    index: HashMap<(String, String), Vec<usize>>,
+}
+
+impl Dump {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut parser = Parser::default();
+
+        for (number, line) in text.lines().enumerate() {
+            parser
+                .line(line)
+                .with_context(|| format!("dump.cs line {}: {}", number + 1, line.trim()))?;
+        }
+
+        let mut dump = Dump::default();
+        for class in parser.classes {
+            dump.insert(class);
+        }
+
+        Ok(dump)
+    }
+
+    fn insert(&mut self, class: Class) {
+        let key = (class.namespace.clone(), class.name.clone());
+
+        self.index.entry(key).or_default().push(self.classes.len());
+        self.classes.push(class);
+    }
+
+    pub fn classes(&self) -> impl Iterator<Item = &Class> {
+        self.classes.iter()
+    }
+
+    pub fn namespaces(&self) -> Vec<&str> {
+        let mut namespaces = self
+            .classes
+            .iter()
+            .map(|c| c.namespace.as_str())
+            .collect::<Vec<_>>();
+
+        namespaces.sort();
+        namespaces.dedup();
+        namespaces
+    }
+
+    /// every class with a namespace and name, which may be defined by more than one assembly
+    pub fn find_classes(&self, namespace: &str, name: &str) -> Vec<&Class> {
+        let key = (namespace.to_string(), name.to_string());
+
+        self.index
+            .get(&key)
+            .map(|indices| indices.iter().map(|&i| &self.classes[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// the class with a namespace and name, which must be defined by only one assembly
+    pub fn get_class(&self, namespace: &str, name: &str) -> Result<&Class> {
+        match self.find_classes(namespace, name).as_slice() {
+            [] => bail!("class not found: {}", full_name(namespace, name)),
+            [class] => Ok(class),
+            classes => {
+                let images = classes
+                    .iter()
+                    .map(|c| c.image.as_deref().unwrap_or("?"))
+                    .collect::<Vec<_>>();
+
+                bail!(
+                    "class {} is defined by more than one assembly: {}",
+                    full_name(namespace, name),
+                    images.join(", ")
+                )
+            }
+        }
+    }
+
+    /// the class defined by a specific assembly, e.g. `Assembly-CSharp.dll`
+    pub fn get_class_in(&self, image: &str, namespace: &str, name: &str) -> Result<&Class> {
+        self.find_classes(namespace, name)
+            .into_iter()
+            .find(|c| c.image.as_deref() == Some(image))
+            .with_context(|| {
+                format!(
+                    "class not found: {} in {}",
+                    full_name(namespace, name),
+                    image
+                )
+            })
+    }
+
+    /// the base class of a class, looked up by name in the class's own namespace, then in any other
+    pub fn get_base(&self, class: &Class) -> Option<&Class> {
+        let base = class.base.as_deref()?;
+        let name = strip_generic_arguments(base);
+
+        self.find_classes(&class.namespace, name)
+            .into_iter()
+            .chain(self.classes.iter().filter(|c| c.name == name))
+            .next()
+    }
+}
+
+impl ClassLayouts for Dump {
+    fn get_field_offset(&self, namespace: &str, class: &str, field: &str) -> Result<usize> {
+        self.get_class(namespace, class)?.get_field_offset(field)
+    }
+}
+
+impl Class {
+    pub fn full_name(&self) -> String {
+        full_name(&self.namespace, &self.name)
+    }
+
+    pub fn get_field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// the offset of an instance field, not counting the object header
+    pub fn get_field_offset(&self, field_name: &str) -> Result<usize> {
+        let field = self
+            .get_field(field_name)
+            .filter(|f| !f.is_static() && !f.is_const())
+            .context(format!("field not found: {}", field_name))?;
+
+        let offset = field
+            .offset
+            .context(format!("field without offset: {}", field_name))?;
+
+        // offsets from il2cpp include the constant-sized class offset of 16 bytes
+        Ok(offset - 16)
+    }
+
+    pub fn instance_fields(&self) -> impl Iterator<Item = &Field> {
+        self.fields
+            .iter()
+            .filter(|f| !f.is_static() && !f.is_const())
+    }
+
+    pub fn static_fields(&self) -> impl Iterator<Item = &Field> {
+        self.fields
+            .iter()
+            .filter(|f| f.is_static() && !f.is_const())
+    }
+
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.iter().any(|a| a == name)
+    }
+
+    /// the integer type an enum is stored as, which il2cpp gives as the type of its `value__`
+    pub fn enum_type(&self) -> Option<&str> {
+        match self.kind {
+            ClassKind::Enum => Some(
+                self.get_field("value__")
+                    .map_or("int", |f| f.type_name.as_str()),
+            ),
+            _ => None,
+        }
+    }
+
+    /// the members of an enum and their values
+    pub fn enum_values(&self) -> Vec<(&str, &str)> {
+        self.fields
+            .iter()
+            .filter(|f| f.is_const())
+            .filter_map(|f| Some((f.name.as_str(), f.value.as_deref()?)))
+            .collect()
+    }
+
+    pub fn get_method(&self, name: &str) -> Option<&Method> {
+        self.methods.iter().find(|m| m.name == name)
+    }
+}
+
+impl Field {
+    pub fn is_static(&self) -> bool {
+        self.modifiers.iter().any(|m| m == "static")
+    }
+
+    pub fn is_const(&self) -> bool {
+        self.modifiers.iter().any(|m| m == "const")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Fields,
+    Properties,
+    Events,
+    Methods,
+}
+
+/// Reads dump.cs a line at a time, since Il2CppDumper writes every declaration on its own line
+#[derive(Debug, Default)]
+struct Parser {
+    images: Vec<Image>,
+    namespace: Option<String>,
+    class: Option<Class>,
+    in_body: bool,
+    in_block_comment: bool,
+    /// the attributes on the declaration that follows them
+    attributes: Vec<String>,
+    section: Option<Section>,
+    rva: Option<u64>,
+    classes: Vec<Class>,
+}
+
+impl Parser {
+    fn line(&mut self, line: &str) -> Result<()> {
+        // generic instances of methods are listed in `/* GenericInstMethod : ... */` blocks
+        let trimmed = line.trim();
+        if self.in_block_comment || trimmed.starts_with("/*") {
+            self.in_block_comment = !trimmed.ends_with("*/");
+            return Ok(());
+        }
+
+        let tokens = Lexer::new(line).collect::<Vec<_>>();
+
+        match tokens.as_slice() {
+            [] => Ok(()),
+
+            // attributes, which are on lines of their own
+            [Token::Word(word), ..] if word.starts_with('[') => {
+                if !self.in_body {
+                    self.attributes.push(attribute_name(word).to_string());
+                }
+
+                Ok(())
+            }
+
+            [Token::Comment(comment)] => {
+                self.comment(comment);
+                Ok(())
+            }
+
+            [Token::Symbol('{')] if self.class.is_some() && !self.in_body => {
+                self.in_body = true;
+                Ok(())
+            }
+
+            [Token::Symbol('}')] if self.in_body => {
+                let class = self.class.take().unwrap();
+                self.classes.push(class);
+                self.in_body = false;
+                Ok(())
+            }
+
+            tokens if self.in_body => self.member(tokens),
+
+            tokens => self.declaration(tokens),
+        }
+    }
+
+    fn comment(&mut self, comment: &str) {
+        if let Some(namespace) = comment.strip_prefix("Namespace:") {
+            self.namespace = Some(namespace.trim().to_string());
+        } else if let Some(image) = comment.strip_prefix("Image ") {
+            // `Image 0: mscorlib.dll - 0`
+            let image = image
+                .split_once(": ")
+                .and_then(|(_, image)| image.rsplit_once(" - "))
+                .and_then(|(name, first)| Some((name, first.parse().ok()?)));
+
+            if let Some((name, first_type)) = image {
+                self.images.push(Image {
+                    name: name.to_string(),
+                    first_type,
+                });
+            }
+        } else if let Some(rva) = comment.strip_prefix("RVA:") {
+            self.rva = rva
+                .split_whitespace()
+                .next()
+                .and_then(|v| v.strip_prefix("0x"))
+                .and_then(|v| u64::from_str_radix(v, 16).ok());
+        } else if self.in_body {
+            self.section = match comment {
+                "Fields" => Some(Section::Fields),
+                "Properties" => Some(Section::Properties),
+                "Events" => Some(Section::Events),
+                "Methods" => Some(Section::Methods),
+                _ => self.section,
+            };
+        }
+    }
+
+    /// `public class List<T> : IList<T>, IReadOnlyList<T> // TypeDefIndex: 123`
+    fn declaration(&mut self, tokens: &[Token]) -> Result<()> {
+        let mut tokens = tokens.iter().peekable();
+
+        let mut kind = None;
+        while let Some(Token::Word(word)) = tokens.peek() {
+            tokens.next();
+
+            kind = match *word {
+                "class" => Some(ClassKind::Class),
+                "struct" => Some(ClassKind::Struct),
+                "enum" => Some(ClassKind::Enum),
+                "interface" => Some(ClassKind::Interface),
+                _ => continue,
+            };
+
+            break;
+        }
+
+        let kind = kind.context("expected a type declaration")?;
+
+        let Some(Token::Word(name)) = tokens.next() else {
+            bail!("expected a type name");
+        };
+
+        let (name, generic_parameters) = split_generic_parameters(name);
+
+        let mut bases = vec![];
+        let mut type_index = None;
+
+        for token in tokens {
+            match token {
+                Token::Word(base) => bases.push(base.to_string()),
+                Token::Symbol(':') | Token::Symbol(',') => {}
+                Token::Comment(comment) => {
+                    type_index = comment
+                        .strip_prefix("TypeDefIndex:")
+                        .and_then(|v| v.trim().parse().ok());
+                }
+                token => bail!("unexpected {:?}", token),
+            }
+        }
+
+        // a class lists its base class first and an enum its underlying type, a struct or
+        // interface only interfaces
+        let base = match kind {
+            ClassKind::Class | ClassKind::Enum if !bases.is_empty() => Some(bases.remove(0)),
+            _ => None,
+        };
+
+        let image = type_index.and_then(|index| {
+            self.images
+                .iter()
+                .rev()
+                .find(|image| image.first_type <= index)
+                .map(|image| image.name.clone())
+        });
+
+        self.class = Some(Class {
+            namespace: self.namespace.take().unwrap_or_default(),
+            name,
+            kind,
+            generic_parameters,
+            base,
+            interfaces: bases,
+            type_index,
+            image,
+            attributes: std::mem::take(&mut self.attributes),
+            fields: vec![],
+            methods: vec![],
+        });
+
+        self.section = None;
+        self.rva = None;
+
+        Ok(())
+    }
+
+    fn member(&mut self, tokens: &[Token]) -> Result<()> {
+        let class = self.class.as_mut().unwrap();
+
+        match self.section {
+            Some(Section::Fields) => class.fields.push(parse_field(tokens)?),
+            Some(Section::Methods) => {
+                let mut method = parse_method(tokens)?;
+                method.rva = self.rva.take();
+                class.methods.push(method);
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// `private readonly List<Bloon> bloons; // 0x18` or `public const int Max = 5;`
+fn parse_field(tokens: &[Token]) -> Result<Field> {
+    let (declaration, rest) = split_at_symbol(tokens, &['=', ';']);
+    let (modifiers, type_name, name) = parse_declaration(declaration)?;
+
+    let value = match rest.first() {
+        Some(Token::Symbol('=')) => {
+            let (value, _) = split_at_symbol(&rest[1..], &[';']);
+            Some(value.iter().map(token_text).collect::<Vec<_>>().join(" "))
+        }
+
+        _ => None,
+    };
+
+    let offset = rest.iter().find_map(|token| match token {
+        Token::Comment(comment) => comment
+            .strip_prefix("0x")
+            .and_then(|v| usize::from_str_radix(v, 16).ok()),
+        _ => None,
+    });
+
+    // fixed size buffers are named like `buffer[16]`
+    let name = name.split('[').next().unwrap().to_string();
+
+    Ok(Field {
+        name,
+        type_name,
+        modifiers,
+        offset,
+        value,
+    })
+}
+
+/// `public override void Process(int elapsed, ref Bloon bloon) { }`
+fn parse_method(tokens: &[Token]) -> Result<Method> {
+    let (declaration, rest) = split_at_symbol(tokens, &['(']);
+    let (modifiers, return_type, name) = parse_declaration(declaration)?;
+
+    let (parameters, _) = split_at_symbol(rest.get(1..).unwrap_or_default(), &[')']);
+
+    let parameters = parameters
+        .split(|t| *t == Token::Symbol(','))
+        .filter(|p| !p.is_empty())
+        .map(|parameter| {
+            let (parameter, _) = split_at_symbol(parameter, &['=']);
+            let (_, type_name, name) = parse_declaration(parameter)?;
+
+            Ok(Parameter {
+                name: name.to_string(),
+                type_name,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Method {
+        name: strip_generic_arguments(name).to_string(),
+        return_type,
+        modifiers,
+        parameters,
+        rva: None,
+    })
+}
+
+/// splits `modifiers type name` into its parts
+fn parse_declaration<'a>(tokens: &[Token<'a>]) -> Result<(Vec<String>, String, &'a str)> {
+    let words = tokens
+        .iter()
+        .filter(|token| !matches!(token, Token::Word(word) if word.starts_with('[')))
+        .map(|token| match token {
+            Token::Word(word) => Ok(*word),
+            token => bail!("unexpected {:?}", token),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let Some((&name, rest)) = words.split_last() else {
+        bail!("expected a name");
+    };
+
+    let modifier_count = rest.iter().take_while(|w| MODIFIERS.contains(w)).count();
+    let (modifiers, type_name) = rest.split_at(modifier_count);
+
+    Ok((
+        modifiers.iter().map(|m| m.to_string()).collect(),
+        type_name.join(" "),
+        name,
+    ))
+}
+
+/// the tokens before the first of some symbols, and the rest starting with that symbol
+fn split_at_symbol<'a, 'b>(
+    tokens: &'b [Token<'a>],
+    symbols: &[char],
+) -> (&'b [Token<'a>], &'b [Token<'a>]) {
+    let end = tokens
+        .iter()
+        .position(|t| matches!(t, Token::Symbol(c) if symbols.contains(c)))
+        .unwrap_or(tokens.len());
+
+    tokens.split_at(end)
+}
+
+fn token_text<'a>(token: &Token<'a>) -> &'a str {
+    match *token {
+        Token::Word(text) | Token::Literal(text) | Token::Comment(text) => text,
+        Token::Symbol(_) => "",
+    }
+}
+
+/// `[FlagsAttribute]` or `[Flags()]` to `Flags`
+fn attribute_name(attribute: &str) -> &str {
+    let name = attribute.trim_start_matches('[').trim_end_matches(']');
+    let name = name.split('(').next().unwrap().trim();
+
+    name.strip_suffix("Attribute").unwrap_or(name)
+}
+
+/// `Dictionary.Entry<TKey, TValue>` to `Dictionary.Entry` and `[TKey, TValue]`
+fn split_generic_parameters(name: &str) -> (String, Vec<String>) {
+    match name.split_once('<') {
+        Some((base, parameters)) if !base.is_empty() => {
+            let parameters = parameters
+                .trim_end_matches('>')
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .collect();
+
+            (base.to_string(), parameters)
+        }
+
+        _ => (name.to_string(), vec![]),
+    }
+}
+
+/// `List<Bloon>` to `List`, leaving compiler generated names like `<roundSet>k__BackingField`
+fn strip_generic_arguments(name: &str) -> &str {
+    match name.find('<') {
+        Some(0) | None => name,
+        Some(i) => &name[..i],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = r#"
+// Image 0: mscorlib.dll - 0
+// Image 1: Assembly-CSharp.dll - 2
+
+// Namespace: System.Collections.Generic
+[DefaultMember("Item")]
+public class List<T> : IList<T>, IReadOnlyList<T> // TypeDefIndex: 0
+{
+	// Fields
+	private T[] _items; // 0x10
+	private int _size; // 0x18
+	private int _version; // 0x1C
+	private static readonly T[] s_emptyArray; // 0x0
+
+	// Properties
+	public int Count { get; }
+
+	// Methods
+
+	// RVA: 0x1234560 Offset: 0x1233960 VA: 0x181234560
+	public void Add(T item) { }
+	/* GenericInstMethod :
+	|
+	|-RVA: 0x2345670 Offset: 0x2344A70 VA: 0x182345670
+	|-List<Tower>.Add
+	*/
+}
+
+// Namespace: 
+public struct Dictionary.Entry<TKey, TValue> // TypeDefIndex: 1
+{
+	// Fields
+	public int hashCode; // 0x0
+	public int next; // 0x4
+	public TKey key; // 0x0
+	public TValue value; // 0x0
+}
+
+// Namespace: Assets.Scripts.Models.Towers
+public class TowerModel : EntityModel // TypeDefIndex: 2
+{
+	// Fields
+	public string baseId; // 0x38
+	public int[] tiers; // 0x40
+	private Dictionary<string, List<UpgradeModel>> <upgrades>k__BackingField; // 0x48
+	public const int MaxTier = 5;
+}
+
+// Namespace: 
+public class PlayerContext.Context // TypeDefIndex: 3
+{
+	// Fields
+	public InputManager inputManager; // 0x10
+}
+
+// Namespace: Assets.Scripts.Models.Towers
+[Flags]
+public enum TowerSet // TypeDefIndex: 4
+{
+	// Fields
+	public byte value__; // 0x0
+	public const TowerSet None = 0;
+	public const TowerSet Primary = 1;
+	public const TowerSet Military = 2;
+	public const TowerSet Hero = 16;
+}
+
+// Namespace: Assets.Scripts.Simulation.Towers
+public enum TargetType // TypeDefIndex: 5
+{
+	// Fields
+	public int value__; // 0x0
+	public const TargetType Unknown = -1;
+	public const TargetType First = 0;
+}
+"#;
+
+    #[test]
+    fn parses_generic_classes() {
+        let dump = Dump::parse(DUMP).unwrap();
+        let list = dump
+            .get_class("System.Collections.Generic", "List")
+            .unwrap();
+
+        assert_eq!(list.kind, ClassKind::Class);
+        assert_eq!(list.generic_parameters, ["T"]);
+        assert_eq!(list.base.as_deref(), Some("IList<T>"));
+        assert_eq!(list.interfaces, ["IReadOnlyList<T>"]);
+        assert_eq!(list.image.as_deref(), Some("mscorlib.dll"));
+        assert!(list.has_attribute("DefaultMember"));
+
+        assert_eq!(list.get_field("_items").unwrap().type_name, "T[]");
+        assert_eq!(list.instance_fields().count(), 3);
+        assert_eq!(list.static_fields().count(), 1);
+
+        // generic instances of a method don't replace the method's own address
+        let add = list.get_method("Add").unwrap();
+        assert_eq!(add.rva, Some(0x1234560));
+        assert_eq!(add.parameters[0].type_name, "T");
+        assert_eq!(list.methods.len(), 1);
+    }
+
+    #[test]
+    fn parses_nested_classes() {
+        let dump = Dump::parse(DUMP).unwrap();
+
+        let entry = dump.get_class("", "Dictionary.Entry").unwrap();
+        assert_eq!(entry.kind, ClassKind::Struct);
+        assert_eq!(entry.generic_parameters, ["TKey", "TValue"]);
+        assert_eq!(entry.base, None);
+
+        let context = dump.get_class("", "PlayerContext.Context").unwrap();
+        assert_eq!(context.full_name(), "PlayerContext.Context");
+        assert_eq!(context.image.as_deref(), Some("Assembly-CSharp.dll"));
+        assert!(dump.get_class("", "Context").is_err());
+    }
+
+    #[test]
+    fn parses_field_offsets() {
+        let dump = Dump::parse(DUMP).unwrap();
+        let model = dump
+            .get_class("Assets.Scripts.Models.Towers", "TowerModel")
+            .unwrap();
+
+        // offsets are given from the start of the object, including its header
+        assert_eq!(model.get_field_offset("baseId").unwrap(), 0x28);
+        assert_eq!(
+            model.get_field_offset("<upgrades>k__BackingField").unwrap(),
+            0x38
+        );
+        assert_eq!(
+            model
+                .get_field("<upgrades>k__BackingField")
+                .unwrap()
+                .type_name,
+            "Dictionary<string, List<UpgradeModel>>"
+        );
+
+        // constants and statics have no offset in an object
+        assert!(model.get_field_offset("MaxTier").is_err());
+        assert!(model.get_field_offset("missing").is_err());
+        assert_eq!(
+            ClassLayouts::get_field_offset(
+                &dump,
+                "Assets.Scripts.Models.Towers",
+                "TowerModel",
+                "tiers"
+            )
+            .unwrap(),
+            0x30
+        );
+
+        let list = dump
+            .get_class("System.Collections.Generic", "List")
+            .unwrap();
+        assert!(list.get_field_offset("s_emptyArray").is_err());
+    }
+
+    #[test]
+    fn parses_enums() {
+        let dump = Dump::parse(DUMP).unwrap();
+
+        let set = dump
+            .get_class("Assets.Scripts.Models.Towers", "TowerSet")
+            .unwrap();
+        assert_eq!(set.kind, ClassKind::Enum);
+        assert!(set.has_attribute("Flags"));
+        assert_eq!(set.enum_type(), Some("byte"));
+        assert_eq!(
+            set.enum_values(),
+            [
+                ("None", "0"),
+                ("Primary", "1"),
+                ("Military", "2"),
+                ("Hero", "16")
+            ]
+        );
+
+        let target = dump
+            .get_class("Assets.Scripts.Simulation.Towers", "TargetType")
+            .unwrap();
+        assert!(!target.has_attribute("Flags"));
+        assert_eq!(target.enum_type(), Some("int"));
+        assert_eq!(target.enum_values()[0], ("Unknown", "-1"));
+
+        let model = dump
+            .get_class("Assets.Scripts.Models.Towers", "TowerModel")
+            .unwrap();
+        assert_eq!(model.enum_type(), None);
+    }
+}
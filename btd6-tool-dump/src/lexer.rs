@@ -0,0 +1,103 @@
+/// A token of a declaration line in dump.cs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    /// a keyword, name or type, including any generic arguments or array brackets, e.g.
+    /// `Dictionary<string, int>`, `int[]` or `<roundSet>k__BackingField`
+    Word(&'a str),
+    /// a string or char literal, including its quotes
+    Literal(&'a str),
+    Symbol(char),
+    /// the text of a `//` comment, after the slashes
+    Comment(&'a str),
+}
+
+const SYMBOLS: &[char] = &[':', ';', ',', '=', '{', '}', '(', ')'];
+
+/// Splits a line of dump.cs into tokens
+pub struct Lexer<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self { text, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    /// takes text up to (not including) the char at `end` bytes into the rest
+    fn take(&mut self, end: usize) -> &'a str {
+        let value = &self.rest()[..end];
+        self.position += end;
+        value
+    }
+
+    fn word(&mut self) -> &'a str {
+        let mut depth = 0usize;
+
+        let end = self
+            .rest()
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '<' | '[' => depth += 1,
+                    '>' | ']' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+
+                depth == 0 && (c.is_whitespace() || SYMBOLS.contains(&c))
+            })
+            .map_or(self.rest().len(), |(i, _)| i);
+
+        self.take(end)
+    }
+
+    fn literal(&mut self, quote: char) -> &'a str {
+        let mut escaped = false;
+
+        let end = self
+            .rest()
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| {
+                let closes = !escaped && c == quote;
+                escaped = !escaped && c == '\\';
+                closes
+            })
+            .map_or(self.rest().len(), |(i, c)| i + c.len_utf8());
+
+        self.take(end)
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let trimmed = self.rest().trim_start();
+        self.position = self.text.len() - trimmed.len();
+
+        let c = trimmed.chars().next()?;
+
+        let token = match c {
+            '/' if trimmed.starts_with("//") => {
+                let comment = self.take(trimmed.len());
+                Token::Comment(comment[2..].trim())
+            }
+
+            '"' | '\'' => Token::Literal(self.literal(c)),
+
+            c if SYMBOLS.contains(&c) => {
+                self.take(c.len_utf8());
+                Token::Symbol(c)
+            }
+
+            _ => Token::Word(self.word()),
+        };
+
+        Some(token)
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+mod dump;
+mod lexer;
+
+pub use dump::{Class, ClassKind, Dump, Field, Method, Parameter};
+
+pub type Result<T> = anyhow::Result<T>;
+
+/// The full name of a class as it appears in script.json, e.g. `Assets.Scripts.Simulation.Simulation`
+pub fn full_name(namespace: &str, name: &str) -> String {
+    match namespace {
+        "" => name.to_string(),
+        _ => format!("{}.{}", namespace, name),
+    }
+}
+
+/// Class layouts from some output of il2cpp, looked up by namespace and class name
+pub trait ClassLayouts {
+    /// the offset of an instance field, not counting the object header
+    fn get_field_offset(&self, namespace: &str, class: &str, field: &str) -> Result<usize>;
+
+    /// offset from the base of GameAssembly.dll of the class's `TypeInfo` pointer, if the layouts
+    /// know where it is
+    fn get_type_info(&self, _namespace: &str, _class: &str) -> Option<u64> {
+        None
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ScriptMetadata {
+    address: u64,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ScriptFile {
+    script_metadata: Vec<ScriptMetadata>,
+}
+
+/// The addresses of metadata globals listed in script.json
+pub struct Script {
+    metadata: HashMap<String, u64>,
+}
+
+impl Script {
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: ScriptFile = serde_json::from_str(text)?;
+
+        let metadata = file
+            .script_metadata
+            .into_iter()
+            .map(|m| (m.name, m.address))
+            .collect();
+
+        Ok(Self { metadata })
+    }
+
+    /// offset from the base of GameAssembly.dll of the class's `TypeInfo` pointer
+    pub fn get_type_info(&self, full_name: &str) -> Option<u64> {
+        self.metadata
+            .get(&format!("{}_TypeInfo", full_name))
+            .copied()
+    }
+}
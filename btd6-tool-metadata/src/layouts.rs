@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context};
+use btd6_tool_dump::ClassLayouts;
+
+use crate::{pe::PeFile, read_u32, read_u64, Metadata, MetadataRegistration, Result};
+
+/// `Il2CppMetadataUsage` of a usage that resolves to a class's `TypeInfo`
+const USAGE_TYPE_INFO: u64 = 1;
+
+/// `Il2CppTypeEnum` of types whose data is the index of their type definition
+const TYPE_VALUE_TYPE: u32 = 0x11;
+const TYPE_CLASS: u32 = 0x12;
+
+/// The field offsets il2cpp compiles into GameAssembly.dll, one table per type definition
+#[derive(Debug, Clone)]
+pub struct FieldOffsets {
+    types: Vec<Option<Vec<i32>>>,
+}
+
+impl FieldOffsets {
+    pub fn read(
+        pe: &PeFile,
+        metadata: &Metadata,
+        registration: &MetadataRegistration,
+    ) -> Result<Self> {
+        let table = pe.get_va(registration.field_offsets())?;
+
+        let mut types = vec![];
+        for (index, ty) in metadata.types.iter().enumerate() {
+            let address = read_u64(table, 8 * index)?;
+
+            // types without instance data have no table
+            if address == 0 {
+                types.push(None);
+                continue;
+            }
+
+            let data = pe.get_va(address)?;
+            let offsets = (0..ty.field_count)
+                .map(|i| Ok(read_u32(data, 4 * i)? as i32))
+                .collect::<Result<Vec<_>>>()?;
+
+            types.push(Some(offsets));
+        }
+
+        Ok(Self { types })
+    }
+
+    /// the offset of a field, counting from the start of the object header
+    pub fn get(&self, type_index: usize, field_index: usize) -> Option<i32> {
+        self.types
+            .get(type_index)?
+            .as_ref()?
+            .get(field_index)
+            .copied()
+    }
+}
+
+/// Where GameAssembly.dll keeps each class's `TypeInfo` pointer, as offsets from its image base
+#[derive(Debug, Clone, Default)]
+pub struct TypeInfoSlots {
+    types: HashMap<usize, u64>,
+}
+
+impl TypeInfoSlots {
+    /// decodes the metadata usages, each a slot initialized to a token of its kind and index
+    /// that il2cpp replaces with what it refers to, and keeps those naming a type definition
+    pub fn read(pe: &PeFile, registration: &MetadataRegistration) -> Result<Self> {
+        let Some((count, usages)) = registration.metadata_usages() else {
+            return Ok(Self::default());
+        };
+
+        let (type_count, types) = registration.types();
+        let types = pe.get_va(types)?;
+        let usages = pe.get_va(usages)?;
+
+        let mut slots = HashMap::new();
+        for index in 0..count {
+            let slot = read_u64(usages, 8 * index)?;
+            let token = read_u64(pe.get_va(slot)?, 0)?;
+
+            if token & 1 == 0 || (token >> 29) & 7 != USAGE_TYPE_INFO {
+                continue;
+            }
+
+            let type_index = ((token & 0x1FFF_FFFE) >> 1) as usize;
+            if type_index >= type_count {
+                continue;
+            }
+
+            // Il2CppType is the type definition index followed by attrs, type and modifier bits
+            let ty = pe.get_va(read_u64(types, 8 * type_index)?)?;
+            let kind = (read_u32(ty, 8)? >> 16) & 0xff;
+
+            if kind == TYPE_CLASS || kind == TYPE_VALUE_TYPE {
+                let definition = read_u64(ty, 0)? as usize;
+                slots
+                    .entry(definition)
+                    .or_insert(slot - pe.headers.image_base);
+            }
+        }
+
+        Ok(Self { types: slots })
+    }
+
+    pub fn get(&self, type_index: usize) -> Option<u64> {
+        self.types.get(&type_index).copied()
+    }
+}
+
+/// Class layouts from global-metadata.dat and GameAssembly.dll, in place of Il2CppDumper's dump.cs
+pub struct Layouts {
+    metadata: Metadata,
+    offsets: FieldOffsets,
+    type_infos: TypeInfoSlots,
+    index: HashMap<(String, String), usize>,
+}
+
+impl Layouts {
+    pub fn new(metadata: Metadata, offsets: FieldOffsets, type_infos: TypeInfoSlots) -> Self {
+        let index = metadata.index_types();
+
+        Self {
+            metadata,
+            offsets,
+            type_infos,
+            index,
+        }
+    }
+
+    /// reads the field offsets and `TypeInfo` slots of the metadata's type definitions from
+    /// GameAssembly.dll
+    pub fn read(pe: &PeFile, metadata: Metadata) -> Result<Self> {
+        let registration = MetadataRegistration::find(pe, &metadata)?;
+
+        let offsets = FieldOffsets::read(pe, &metadata, &registration)?;
+        let type_infos = TypeInfoSlots::read(pe, &registration)?;
+
+        Ok(Self::new(metadata, offsets, type_infos))
+    }
+
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+}
+
+impl Layouts {
+    fn get_type_index(&self, namespace: &str, class: &str) -> Option<usize> {
+        self.index
+            .get(&(namespace.to_string(), class.to_string()))
+            .copied()
+    }
+}
+
+impl ClassLayouts for Layouts {
+    fn get_field_offset(&self, namespace: &str, class: &str, field: &str) -> Result<usize> {
+        let type_index = self
+            .get_type_index(namespace, class)
+            .context("class not found")?;
+
+        let ty = &self.metadata.types[type_index];
+
+        let field_index = self
+            .metadata
+            .get_fields(ty)
+            .iter()
+            .position(|f| f.name == field)
+            .context(format!("field not found: {}", field))?;
+
+        let offset = self
+            .offsets
+            .get(type_index, field_index)
+            .context(format!("no offset for field: {}", field))?;
+
+        // like dump.cs, offsets include the 16 byte object header, and static fields have none
+        match usize::try_from(offset) {
+            Ok(offset) if offset >= 16 => Ok(offset - 16),
+            _ => bail!("not an instance field: {}", field),
+        }
+    }
+
+    fn get_type_info(&self, namespace: &str, class: &str) -> Option<u64> {
+        self.type_infos.get(self.get_type_index(namespace, class)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MetadataBuilder, PeBuilder, IMAGE_BASE};
+
+    const NAMESPACE: &str = "Assets.Scripts.Simulation.Towers";
+
+    /// encodes a metadata usage like il2cpp initializes its slot
+    fn usage(kind: u64, index: u64) -> u64 {
+        (kind << 29) | (index << 1) | 1
+    }
+
+    #[test]
+    fn reads_field_offsets_and_type_infos() {
+        let mut builder = MetadataBuilder::new();
+        let tower = builder.ty(NAMESPACE, "Tower", &["id", "model", "count"]);
+        builder.ty(NAMESPACE, "TowerModel", &["name"]);
+        builder.ty(NAMESPACE, "Empty", &[]);
+        let metadata = Metadata::parse(&builder.build()).unwrap();
+
+        let mut pe = PeBuilder::new();
+
+        // the static field has no offset in the object
+        let tower_offsets = pe.i32s(&[0x10, 0x18, -1]);
+        let model_offsets = pe.i32s(&[0x20]);
+        let field_offsets = pe.u64s(&[tower_offsets, model_offsets, 0]);
+
+        // Il2CppTypes for Tower, TowerModel as a value type, and int
+        let tower_type = pe.u64s(&[tower as u64, 0x12 << 16]);
+        let model_type = pe.u64s(&[1, 0x11 << 16]);
+        let int_type = pe.u64s(&[0, 0x08 << 16]);
+        let types = pe.u64s(&[tower_type, model_type, int_type]);
+
+        let tower_slot = pe.u64s(&[usage(1, 0)]);
+        let method_slot = pe.u64s(&[usage(6, 0)]);
+        let int_slot = pe.u64s(&[usage(1, 2)]);
+        let model_slot = pe.u64s(&[usage(1, 1)]);
+        let duplicate_slot = pe.u64s(&[usage(1, 0)]);
+        let usages = pe.u64s(&[
+            tower_slot,
+            method_slot,
+            int_slot,
+            model_slot,
+            duplicate_slot,
+        ]);
+
+        pe.registration(&[
+            (0, 0),
+            (0, 0),
+            (0, 0),
+            (3, types),
+            (0, 0),
+            (3, field_offsets),
+            (3, field_offsets),
+            (5, usages),
+        ]);
+
+        let file = pe.build();
+        let layouts = Layouts::read(&PeFile::parse(&file).unwrap(), metadata).unwrap();
+
+        let offset = |class, field| layouts.get_field_offset(NAMESPACE, class, field).ok();
+        assert_eq!(offset("Tower", "id"), Some(0));
+        assert_eq!(offset("Tower", "model"), Some(8));
+        assert_eq!(offset("Tower", "count"), None);
+        assert_eq!(offset("Tower", "missing"), None);
+        assert_eq!(offset("TowerModel", "name"), Some(0x10));
+        assert_eq!(offset("Missing", "name"), None);
+
+        assert_eq!(
+            layouts.get_type_info(NAMESPACE, "Tower"),
+            Some(tower_slot - IMAGE_BASE)
+        );
+        assert_eq!(
+            layouts.get_type_info(NAMESPACE, "TowerModel"),
+            Some(model_slot - IMAGE_BASE)
+        );
+        assert_eq!(layouts.get_type_info(NAMESPACE, "Empty"), None);
+    }
+
+    #[test]
+    fn reads_no_type_infos_without_metadata_usages() {
+        let mut builder = MetadataBuilder::new();
+        builder.ty(NAMESPACE, "Tower", &["id"]);
+        let metadata = Metadata::parse(&builder.build()).unwrap();
+
+        let mut pe = PeBuilder::new();
+        let tower_offsets = pe.i32s(&[0x10]);
+        let field_offsets = pe.u64s(&[tower_offsets]);
+        pe.registration(&[
+            (0, 0),
+            (0, 0),
+            (0, 0),
+            (0, 0),
+            (0, 0),
+            (1, field_offsets),
+            (1, field_offsets),
+            (0, 0),
+        ]);
+
+        let file = pe.build();
+        let layouts = Layouts::read(&PeFile::parse(&file).unwrap(), metadata).unwrap();
+
+        assert_eq!(
+            layouts.get_field_offset(NAMESPACE, "Tower", "id").ok(),
+            Some(0)
+        );
+        assert_eq!(layouts.get_type_info(NAMESPACE, "Tower"), None);
+    }
+}
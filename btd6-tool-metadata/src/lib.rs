@@ -0,0 +1,322 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{bail, Context};
+
+mod layouts;
+pub mod pe;
+mod registration;
+#[cfg(test)]
+mod testing;
+
+pub use layouts::{FieldOffsets, Layouts, TypeInfoSlots};
+pub use registration::MetadataRegistration;
+
+pub type Result<T> = anyhow::Result<T>;
+
+pub const SANITY: u32 = 0xFAB11BAF;
+
+/// versions whose header and definition layouts we know, Unity 2020.2 through Unity 6
+pub const SUPPORTED_VERSIONS: std::ops::RangeInclusive<u32> = 27..=31;
+
+/// index of each (offset, size) pair in the header, after the sanity and version fields
+mod section {
+    pub const STRINGS: usize = 2;
+    pub const FIELDS: usize = 11;
+    pub const NESTED_TYPES: usize = 15;
+    pub const TYPE_DEFINITIONS: usize = 19;
+}
+
+/// size of Il2CppTypeDefinition
+const TYPE_DEFINITION_SIZE: usize = 88;
+
+/// size of Il2CppFieldDefinition
+const FIELD_DEFINITION_SIZE: usize = 12;
+
+#[derive(Debug, Clone)]
+pub struct TypeDefinition {
+    pub name: String,
+    pub namespace: String,
+    /// index in `Metadata::types` of the type this one is nested in
+    pub declaring_type: Option<usize>,
+
+    pub flags: u32,
+    pub field_start: usize,
+    pub field_count: usize,
+    pub nested_type_start: usize,
+    pub nested_type_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldDefinition {
+    pub name: String,
+    /// index into the Il2CppType table in GameAssembly.dll
+    pub type_index: i32,
+    pub token: u32,
+}
+
+/// The parts of il2cpp's global-metadata.dat needed to name classes and their fields
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    pub version: u32,
+    pub types: Vec<TypeDefinition>,
+    pub fields: Vec<FieldDefinition>,
+}
+
+impl Metadata {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if read_u32(data, 0)? != SANITY {
+            bail!("not a global-metadata.dat file");
+        }
+
+        let version = read_u32(data, 4)?;
+        if !SUPPORTED_VERSIONS.contains(&version) {
+            bail!("unsupported metadata version: {}", version);
+        }
+
+        let strings = get_section(data, section::STRINGS)?;
+
+        let fields = get_section(data, section::FIELDS)?
+            .chunks_exact(FIELD_DEFINITION_SIZE)
+            .map(|field| {
+                Ok(FieldDefinition {
+                    name: read_string(strings, read_u32(field, 0)?)?,
+                    type_index: read_u32(field, 4)? as i32,
+                    token: read_u32(field, 8)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut types = get_section(data, section::TYPE_DEFINITIONS)?
+            .chunks_exact(TYPE_DEFINITION_SIZE)
+            .map(|ty| {
+                Ok(TypeDefinition {
+                    name: read_string(strings, read_u32(ty, 0)?)?,
+                    namespace: read_string(strings, read_u32(ty, 4)?)?,
+                    declaring_type: None,
+
+                    flags: read_u32(ty, 28)?,
+                    field_start: read_u32(ty, 32)? as usize,
+                    nested_type_start: read_u32(ty, 48)? as usize,
+                    field_count: read_u16(ty, 68)? as usize,
+                    nested_type_count: read_u16(ty, 72)? as usize,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for ty in &types {
+            let end = ty.field_start.checked_add(ty.field_count);
+
+            if ty.field_count > 0 && end.is_none_or(|end| end > fields.len()) {
+                bail!("fields of {} out of range", ty.name);
+            }
+        }
+
+        let nested_types = get_section(data, section::NESTED_TYPES)?;
+
+        for index in 0..types.len() {
+            let start = types[index].nested_type_start;
+
+            for i in start..start + types[index].nested_type_count {
+                let nested = read_u32(nested_types, 4 * i)? as usize;
+
+                types
+                    .get_mut(nested)
+                    .context("nested type out of range")?
+                    .declaring_type = Some(index);
+            }
+        }
+
+        // a type nested in itself would make its name endless
+        for (index, ty) in types.iter().enumerate() {
+            let mut declaring_type = ty.declaring_type;
+
+            for _ in 0..types.len() {
+                match declaring_type {
+                    Some(parent) => declaring_type = types[parent].declaring_type,
+                    None => break,
+                }
+            }
+
+            if declaring_type.is_some() {
+                bail!("type {} ({}) is nested in itself", index, ty.name);
+            }
+        }
+
+        Ok(Self {
+            version,
+            types,
+            fields,
+        })
+    }
+
+    /// the name of a type as Il2CppDumper prints it, with nested types as `Outer.Inner`
+    pub fn get_type_name(&self, index: usize) -> String {
+        let ty = &self.types[index];
+
+        match ty.declaring_type {
+            Some(parent) => format!("{}.{}", self.get_type_name(parent), ty.name),
+            None => ty.name.clone(),
+        }
+    }
+
+    pub fn get_fields(&self, ty: &TypeDefinition) -> &[FieldDefinition] {
+        // types without fields have a field_start of -1
+        match ty.field_count {
+            0 => &[],
+            count => &self.fields[ty.field_start..ty.field_start + count],
+        }
+    }
+
+    /// maps (namespace, name) to type index, keeping the first of any duplicates like the dump.cs lookup
+    pub fn index_types(&self) -> HashMap<(String, String), usize> {
+        let mut index = HashMap::new();
+
+        for (i, ty) in self.types.iter().enumerate() {
+            index
+                .entry((ty.namespace.clone(), self.get_type_name(i)))
+                .or_insert(i);
+        }
+
+        index
+    }
+}
+
+fn get_section(data: &[u8], index: usize) -> Result<&[u8]> {
+    let offset = read_u32(data, 8 + 8 * index)? as usize;
+    let size = read_u32(data, 12 + 8 * index)? as usize;
+
+    data.get(offset..offset + size)
+        .context("metadata section out of range")
+}
+
+fn read_string(strings: &[u8], index: u32) -> Result<String> {
+    let data = strings
+        .get(index as usize..)
+        .context("string index out of range")?;
+
+    let len = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+
+    Ok(String::from_utf8(data[..len].to_vec())?)
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    let bytes = data.get(offset..offset + 2).context("read out of range")?;
+
+    Ok(u16::from_le_bytes(bytes.try_into()?))
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    let bytes = data.get(offset..offset + 4).context("read out of range")?;
+
+    Ok(u32::from_le_bytes(bytes.try_into()?))
+}
+
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    let bytes = data.get(offset..offset + 8).context("read out of range")?;
+
+    Ok(u64::from_le_bytes(bytes.try_into()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MetadataBuilder;
+
+    #[test]
+    fn parses_types_fields_and_nesting() {
+        let mut builder = MetadataBuilder::new();
+        let tower = builder.ty(
+            "Assets.Scripts.Simulation.Towers",
+            "Tower",
+            &["id", "model"],
+        );
+        let entry = builder.nested(tower, "Entry", &["value"]);
+        let inner = builder.nested(entry, "Inner", &[]);
+        let empty = builder.ty("", "Empty", &[]);
+
+        let metadata = Metadata::parse(&builder.build()).unwrap();
+
+        assert_eq!(metadata.version, 29);
+        assert_eq!(metadata.types.len(), 4);
+
+        let names = |ty: &TypeDefinition| {
+            metadata
+                .get_fields(ty)
+                .iter()
+                .map(|f| f.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let ty = &metadata.types[tower];
+        assert_eq!(ty.namespace, "Assets.Scripts.Simulation.Towers");
+        assert_eq!(ty.declaring_type, None);
+        assert_eq!(names(ty), ["id", "model"]);
+
+        assert_eq!(metadata.types[entry].declaring_type, Some(tower));
+        assert_eq!(names(&metadata.types[entry]), ["value"]);
+        assert_eq!(metadata.types[inner].declaring_type, Some(entry));
+        assert!(names(&metadata.types[empty]).is_empty());
+
+        assert_eq!(metadata.get_type_name(inner), "Tower.Entry.Inner");
+        assert_eq!(
+            metadata
+                .index_types()
+                .get(&("".to_string(), "Tower.Entry".to_string())),
+            Some(&entry)
+        );
+    }
+
+    #[test]
+    fn rejects_fields_out_of_range() {
+        let mut builder = MetadataBuilder::new();
+        let tower = builder.ty("", "Tower", &["id", "model"]);
+        builder.ty("", "Bloon", &["model"]);
+
+        assert!(Metadata::parse(&builder.build()).is_ok());
+
+        // the last field is the third, not the fourth
+        builder.field_start(tower, 2);
+        let error = Metadata::parse(&builder.build()).unwrap_err();
+        assert_eq!(error.to_string(), "fields of Tower out of range");
+
+        builder.field_start(tower, u32::MAX);
+        assert!(Metadata::parse(&builder.build()).is_err());
+    }
+
+    #[test]
+    fn rejects_types_nested_in_themselves() {
+        let mut builder = MetadataBuilder::new();
+        let outer = builder.ty("", "Outer", &[]);
+        let inner = builder.nested(outer, "Inner", &[]);
+
+        assert!(Metadata::parse(&builder.build()).is_ok());
+
+        builder.nest(outer, inner);
+        let error = Metadata::parse(&builder.build()).unwrap_err();
+        assert_eq!(error.to_string(), "type 0 (Outer) is nested in itself");
+
+        let mut builder = MetadataBuilder::new();
+        let ty = builder.ty("", "Type", &[]);
+        builder.nest(ty, ty);
+        assert!(Metadata::parse(&builder.build()).is_err());
+    }
+
+    #[test]
+    fn rejects_other_files_and_versions() {
+        let mut builder = MetadataBuilder::new();
+        builder.ty("", "Type", &[]);
+
+        assert!(Metadata::parse(&builder.build()).is_ok());
+        assert!(Metadata::parse(&builder.version(24).build()).is_err());
+        assert!(Metadata::parse(b"MZ\0\0\0\0\0\0").is_err());
+
+        // sections past the end of the file
+        let mut data = builder.version(29).build();
+        data.truncate(data.len() - 1);
+        assert!(Metadata::parse(&data).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::{bail, Context};
+
+use crate::{read_u16, read_u32, read_u64, Result};
+
+const IMAGE_SCN_CNT_CODE: u32 = 0x20;
+const IMAGE_SCN_CNT_INITIALIZED_DATA: u32 = 0x40;
+
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub name: String,
+    pub virtual_address: u32,
+    pub virtual_size: u32,
+    pub raw_offset: u32,
+    pub raw_size: u32,
+    pub characteristics: u32,
+}
+
+impl Section {
+    pub fn is_code(&self) -> bool {
+        self.characteristics & IMAGE_SCN_CNT_CODE != 0
+    }
+
+    pub fn is_data(&self) -> bool {
+        self.characteristics & IMAGE_SCN_CNT_INITIALIZED_DATA != 0 && !self.is_code()
+    }
+
+    pub fn contains_rva(&self, rva: u64) -> bool {
+        let start = self.virtual_address as u64;
+
+        (start..start + self.virtual_size.max(self.raw_size) as u64).contains(&rva)
+    }
+}
+
+/// The headers of a 64-bit PE image, either as a file on disk or as mapped into a process
+#[derive(Debug, Clone)]
+pub struct PeHeaders {
+    pub image_base: u64,
+    pub timestamp: u32,
+    pub sections: Vec<Section>,
+}
+
+impl PeHeaders {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.get(0..2) != Some(b"MZ") {
+            bail!("missing DOS header");
+        }
+
+        let pe = read_u32(data, 0x3c)? as usize;
+        if read_u32(data, pe)? != 0x4550 {
+            bail!("invalid PE header");
+        }
+
+        let section_count = read_u16(data, pe + 6)? as usize;
+        let timestamp = read_u32(data, pe + 8)?;
+        let optional_size = read_u16(data, pe + 20)? as usize;
+
+        let optional = pe + 24;
+        if read_u16(data, optional)? != 0x20b {
+            bail!("not a 64-bit image");
+        }
+
+        let image_base = read_u64(data, optional + 24)?;
+
+        let mut sections = vec![];
+        for i in 0..section_count {
+            let header = optional + optional_size + 40 * i;
+
+            let name = data
+                .get(header..header + 8)
+                .context("section out of range")?;
+            let name = String::from_utf8_lossy(name)
+                .trim_end_matches('\0')
+                .to_string();
+
+            sections.push(Section {
+                name,
+                virtual_size: read_u32(data, header + 8)?,
+                virtual_address: read_u32(data, header + 12)?,
+                raw_size: read_u32(data, header + 16)?,
+                raw_offset: read_u32(data, header + 20)?,
+                characteristics: read_u32(data, header + 36)?,
+            });
+        }
+
+        Ok(Self {
+            image_base,
+            timestamp,
+            sections,
+        })
+    }
+
+    pub fn find_section(&self, rva: u64) -> Option<&Section> {
+        self.sections.iter().find(|s| s.contains_rva(rva))
+    }
+}
+
+/// A PE image read from disk, addressed by virtual address like the loaded module would be
+pub struct PeFile<'a> {
+    pub headers: PeHeaders,
+    data: &'a [u8],
+}
+
+impl<'a> PeFile<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        let headers = PeHeaders::parse(data)?;
+
+        Ok(Self { headers, data })
+    }
+
+    /// the file contents backing a virtual address, up to the end of its section
+    pub fn get_va(&self, va: u64) -> Result<&'a [u8]> {
+        let rva = va
+            .checked_sub(self.headers.image_base)
+            .context("address before image base")?;
+
+        let section = self
+            .headers
+            .find_section(rva)
+            .context(format!("address not in image: {:#x}", va))?;
+
+        let offset = (rva - section.virtual_address as u64) as usize;
+        if offset >= section.raw_size as usize {
+            bail!("address not backed by file: {:#x}", va);
+        }
+
+        let start = section.raw_offset as usize + offset;
+        let end = (section.raw_offset + section.raw_size) as usize;
+
+        self.data.get(start..end).context("section out of range")
+    }
+
+    pub fn contains_va(&self, va: u64) -> bool {
+        va.checked_sub(self.headers.image_base)
+            .is_some_and(|rva| self.headers.find_section(rva).is_some())
+    }
+
+    /// each initialized data section's virtual address and file contents
+    pub fn data_sections(&self) -> impl Iterator<Item = (u64, &'a [u8])> + '_ {
+        self.headers
+            .sections
+            .iter()
+            .filter(|s| s.is_data())
+            .filter_map(|s| {
+                let start = s.raw_offset as usize;
+                let data = self.data.get(start..start + s.raw_size as usize)?;
+
+                Some((self.headers.image_base + s.virtual_address as u64, data))
+            })
+    }
+}
@@ -0,0 +1,193 @@
+use anyhow::bail;
+
+use crate::{pe::PeFile, read_u64, Metadata, Result};
+
+/// (count, pointer) pairs in Il2CppMetadataRegistration, from genericClasses through
+/// typeDefinitionsSizes, which every candidate must have
+const REQUIRED_PAIRS: usize = 7;
+const MAX_COUNT: u64 = 0x100000;
+
+/// index of each (count, pointer) pair used here
+mod pair {
+    pub const TYPES: usize = 3;
+    pub const FIELD_OFFSETS: usize = 5;
+    pub const TYPE_DEFINITIONS_SIZES: usize = 6;
+    pub const METADATA_USAGES: usize = 7;
+}
+
+/// il2cpp's `Il2CppMetadataRegistration`, which holds GameAssembly.dll's tables indexed by metadata,
+/// found either in the file or in the loaded module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataRegistration {
+    pub address: u64,
+    pairs: [(u64, u64); REQUIRED_PAIRS],
+    /// metadataUsagesCount and metadataUsages, if the pair after typeDefinitionsSizes looks like them
+    metadata_usages: Option<(u64, u64)>,
+}
+
+impl MetadataRegistration {
+    /// candidates in a data section starting at `address`, found by their (count, pointer) pairs
+    /// where fieldOffsetsCount and typeDefinitionsSizesCount both equal the number of type
+    /// definitions, and every pointer is inside the image
+    pub fn find_iter<'a>(
+        data: &'a [u8],
+        address: u64,
+        contains: impl Fn(u64) -> bool + 'a,
+    ) -> impl Iterator<Item = Self> + 'a {
+        (0..data.len().saturating_sub(16 * REQUIRED_PAIRS))
+            .step_by(8)
+            .filter_map(move |position| {
+                let pair = |i: usize| {
+                    let start = position + 16 * i;
+                    Some((read_u64(data, start).ok()?, read_u64(data, start + 8).ok()?))
+                };
+
+                let mut pairs = [(0, 0); REQUIRED_PAIRS];
+                for (i, entry) in pairs.iter_mut().enumerate() {
+                    *entry = pair(i)?;
+                }
+
+                let (field_offsets, _) = pairs[pair::FIELD_OFFSETS];
+                let (type_sizes, _) = pairs[pair::TYPE_DEFINITIONS_SIZES];
+                if field_offsets == 0 || field_offsets != type_sizes {
+                    return None;
+                }
+
+                let valid = pairs.iter().all(|&pair| match pair {
+                    (0, pointer) => pointer == 0 || contains(pointer),
+                    (count, pointer) => count <= MAX_COUNT && contains(pointer),
+                });
+
+                if !valid {
+                    return None;
+                }
+
+                let metadata_usages = pair(pair::METADATA_USAGES)
+                    .filter(|&(count, pointer)| count <= MAX_COUNT && contains(pointer));
+
+                Some(Self {
+                    address: address + position as u64,
+                    pairs,
+                    metadata_usages,
+                })
+            })
+    }
+
+    /// finds the registration in a GameAssembly.dll file for the metadata's type definitions
+    pub fn find(pe: &PeFile, metadata: &Metadata) -> Result<Self> {
+        let count = metadata.types.len();
+
+        let found = pe.data_sections().find_map(|(address, data)| {
+            Self::find_iter(data, address, |va| pe.contains_va(va))
+                .find(|r| r.type_definition_count() == count)
+        });
+
+        match found {
+            Some(registration) => Ok(registration),
+            None => bail!("metadata registration not found"),
+        }
+    }
+
+    pub fn type_definition_count(&self) -> usize {
+        self.pairs[pair::FIELD_OFFSETS].0 as usize
+    }
+
+    /// the number of `Il2CppType`s, and the address of the table of pointers to them
+    pub fn types(&self) -> (usize, u64) {
+        let (count, pointer) = self.pairs[pair::TYPES];
+        (count as usize, pointer)
+    }
+
+    /// the address of the table of pointers to each type definition's field offsets
+    pub fn field_offsets(&self) -> u64 {
+        self.pairs[pair::FIELD_OFFSETS].1
+    }
+
+    /// the number of metadata usages, and the address of the table of pointers to their slots
+    pub fn metadata_usages(&self) -> Option<(usize, u64)> {
+        self.metadata_usages
+            .map(|(count, pointer)| (count as usize, pointer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::PeBuilder;
+
+    #[test]
+    fn finds_the_registration_for_the_metadata_type_count() {
+        let mut pe = PeBuilder::new();
+        let table = pe.alloc(&[0; 64]);
+
+        // a registration for some other metadata comes first
+        let decoy = pe.registration(&[
+            (2, table),
+            (2, table),
+            (0, 0),
+            (0, 0),
+            (0, 0),
+            (2, table),
+            (2, table),
+            (0, 0),
+        ]);
+        let real = pe.registration(&[
+            (0, 0),
+            (0, 0),
+            (0, 0),
+            (4, table),
+            (0, 0),
+            (3, table),
+            (3, table),
+            (1, table),
+        ]);
+
+        let file = pe.build();
+        let pe = PeFile::parse(&file).unwrap();
+        let data = pe.data_sections().next().unwrap();
+
+        let found = MetadataRegistration::find_iter(data.1, data.0, |va| pe.contains_va(va))
+            .map(|r| r.address)
+            .collect::<Vec<_>>();
+        assert_eq!(found, [decoy, real]);
+
+        let registration = MetadataRegistration::find(&pe, &metadata_with(3)).unwrap();
+        assert_eq!(registration.address, real);
+        assert_eq!(registration.type_definition_count(), 3);
+        assert_eq!(registration.types(), (4, table));
+        assert_eq!(registration.field_offsets(), table);
+        assert_eq!(registration.metadata_usages(), Some((1, table)));
+
+        assert!(MetadataRegistration::find(&pe, &metadata_with(5)).is_err());
+    }
+
+    #[test]
+    fn rejects_pointers_outside_the_image() {
+        let mut pe = PeBuilder::new();
+        let table = pe.alloc(&[0; 64]);
+        pe.registration(&[
+            (0, 0),
+            (0, 0),
+            (0, 0),
+            (4, 0x1234),
+            (0, 0),
+            (3, table),
+            (3, table),
+            (0, 0),
+        ]);
+
+        let file = pe.build();
+        let pe = PeFile::parse(&file).unwrap();
+
+        assert!(MetadataRegistration::find(&pe, &metadata_with(3)).is_err());
+    }
+
+    fn metadata_with(types: usize) -> Metadata {
+        let mut builder = crate::testing::MetadataBuilder::new();
+        for i in 0..types {
+            builder.ty("", &format!("Type{}", i), &[]);
+        }
+
+        Metadata::parse(&builder.build()).unwrap()
+    }
+}
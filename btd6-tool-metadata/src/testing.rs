@@ -0,0 +1,222 @@
+//! Synthetic global-metadata.dat and GameAssembly.dll files, laid out like il2cpp writes them
+
+use crate::{section, FIELD_DEFINITION_SIZE, SANITY, TYPE_DEFINITION_SIZE};
+
+const HEADER_SIZE: usize = 8 + 8 * (section::TYPE_DEFINITIONS + 1);
+
+pub const IMAGE_BASE: u64 = 0x1_8000_0000;
+pub const TIMESTAMP: u32 = 0x5eed0001;
+
+const PE_HEADER: usize = 0x80;
+const OPTIONAL_HEADER_SIZE: usize = 240;
+const SECTION_RVA: u64 = 0x1000;
+const SECTION_OFFSET: usize = 0x400;
+
+struct TypeSpec {
+    namespace: String,
+    name: String,
+    fields: Vec<String>,
+    /// where the type's fields start, if not right after the previous type's
+    field_start: Option<u32>,
+    declaring_type: Option<usize>,
+}
+
+/// Builds a global-metadata.dat with the strings, fields, nested types and type definitions
+/// sections filled in
+pub struct MetadataBuilder {
+    version: u32,
+    types: Vec<TypeSpec>,
+}
+
+impl MetadataBuilder {
+    pub fn new() -> Self {
+        Self {
+            version: 29,
+            types: vec![],
+        }
+    }
+
+    pub fn version(&mut self, version: u32) -> &mut Self {
+        self.version = version;
+        self
+    }
+
+    /// adds a type definition, returning its index
+    pub fn ty(&mut self, namespace: &str, name: &str, fields: &[&str]) -> usize {
+        self.types.push(TypeSpec {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            fields: fields.iter().map(|f| f.to_string()).collect(),
+            field_start: None,
+            declaring_type: None,
+        });
+
+        self.types.len() - 1
+    }
+
+    /// adds a type definition nested in another, which has no namespace of its own
+    pub fn nested(&mut self, declaring_type: usize, name: &str, fields: &[&str]) -> usize {
+        let index = self.ty("", name, fields);
+        self.nest(index, declaring_type);
+
+        index
+    }
+
+    /// moves a type into another, which may be one nested in it
+    pub fn nest(&mut self, ty: usize, declaring_type: usize) -> &mut Self {
+        self.types[ty].declaring_type = Some(declaring_type);
+        self
+    }
+
+    /// points a type's fields somewhere else in the fields section
+    pub fn field_start(&mut self, ty: usize, start: u32) -> &mut Self {
+        self.types[ty].field_start = Some(start);
+        self
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut strings = vec![0];
+        let mut string = |s: &str| {
+            let index = strings.len() as u32;
+            strings.extend_from_slice(s.as_bytes());
+            strings.push(0);
+            index
+        };
+
+        let mut fields = vec![];
+        let mut nested = vec![];
+        let mut types = vec![];
+
+        for (index, ty) in self.types.iter().enumerate() {
+            let field_start = match ty.fields.len() {
+                0 => u32::MAX,
+                _ => (fields.len() / FIELD_DEFINITION_SIZE) as u32,
+            };
+            let field_start = ty.field_start.unwrap_or(field_start);
+
+            for (i, field) in ty.fields.iter().enumerate() {
+                fields.extend_from_slice(&string(field).to_le_bytes());
+                fields.extend_from_slice(&(i as u32).to_le_bytes());
+                fields.extend_from_slice(&(0x0400_0001 + i as u32).to_le_bytes());
+            }
+
+            let nested_start = (nested.len() / 4) as u32;
+            let children = (0..self.types.len())
+                .filter(|&i| self.types[i].declaring_type == Some(index))
+                .collect::<Vec<_>>();
+            for &child in &children {
+                nested.extend_from_slice(&(child as u32).to_le_bytes());
+            }
+
+            let mut definition = vec![0; TYPE_DEFINITION_SIZE];
+            definition[0..4].copy_from_slice(&string(&ty.name).to_le_bytes());
+            definition[4..8].copy_from_slice(&string(&ty.namespace).to_le_bytes());
+            definition[32..36].copy_from_slice(&field_start.to_le_bytes());
+            definition[48..52].copy_from_slice(&nested_start.to_le_bytes());
+            definition[68..70].copy_from_slice(&(ty.fields.len() as u16).to_le_bytes());
+            definition[72..74].copy_from_slice(&(children.len() as u16).to_le_bytes());
+            types.extend_from_slice(&definition);
+        }
+
+        let mut data = vec![0; HEADER_SIZE];
+        data[0..4].copy_from_slice(&SANITY.to_le_bytes());
+        data[4..8].copy_from_slice(&self.version.to_le_bytes());
+
+        for (index, contents) in [
+            (section::STRINGS, strings),
+            (section::FIELDS, fields),
+            (section::NESTED_TYPES, nested),
+            (section::TYPE_DEFINITIONS, types),
+        ] {
+            let header = 8 + 8 * index;
+            let offset = data.len() as u32;
+            data[header..header + 4].copy_from_slice(&offset.to_le_bytes());
+            data[header + 4..header + 8].copy_from_slice(&(contents.len() as u32).to_le_bytes());
+            data.extend_from_slice(&contents);
+        }
+
+        data
+    }
+}
+
+/// Builds a 64-bit PE file with a single initialized data section, allocated from the start
+pub struct PeBuilder {
+    section: Vec<u8>,
+}
+
+impl PeBuilder {
+    pub fn new() -> Self {
+        Self { section: vec![] }
+    }
+
+    /// appends 8 byte aligned data to the section, returning its virtual address
+    pub fn alloc(&mut self, data: &[u8]) -> u64 {
+        let va = IMAGE_BASE + SECTION_RVA + self.section.len() as u64;
+
+        self.section.extend_from_slice(data);
+        self.section
+            .resize(self.section.len().next_multiple_of(8), 0);
+
+        va
+    }
+
+    pub fn u64s(&mut self, values: &[u64]) -> u64 {
+        let data = values
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        self.alloc(&data)
+    }
+
+    pub fn i32s(&mut self, values: &[i32]) -> u64 {
+        let data = values
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        self.alloc(&data)
+    }
+
+    /// an Il2CppMetadataRegistration from its (count, pointer) pairs
+    pub fn registration(&mut self, pairs: &[(u64, u64)]) -> u64 {
+        let values = pairs
+            .iter()
+            .flat_map(|&(count, pointer)| [count, pointer])
+            .collect::<Vec<_>>();
+
+        self.u64s(&values)
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut data = vec![0; SECTION_OFFSET];
+        data[0..2].copy_from_slice(b"MZ");
+        data[0x3c..0x40].copy_from_slice(&(PE_HEADER as u32).to_le_bytes());
+
+        let pe = PE_HEADER;
+        data[pe..pe + 4].copy_from_slice(b"PE\0\0");
+        data[pe + 4..pe + 6].copy_from_slice(&0x8664u16.to_le_bytes());
+        data[pe + 6..pe + 8].copy_from_slice(&1u16.to_le_bytes());
+        data[pe + 8..pe + 12].copy_from_slice(&TIMESTAMP.to_le_bytes());
+        data[pe + 20..pe + 22].copy_from_slice(&(OPTIONAL_HEADER_SIZE as u16).to_le_bytes());
+
+        let optional = pe + 24;
+        data[optional..optional + 2].copy_from_slice(&0x20bu16.to_le_bytes());
+        data[optional + 24..optional + 32].copy_from_slice(&IMAGE_BASE.to_le_bytes());
+
+        let size = (self.section.len() as u32).max(8);
+        let header = optional + OPTIONAL_HEADER_SIZE;
+        data[header..header + 8].copy_from_slice(b".data\0\0\0");
+        data[header + 8..header + 12].copy_from_slice(&size.to_le_bytes());
+        data[header + 12..header + 16].copy_from_slice(&(SECTION_RVA as u32).to_le_bytes());
+        data[header + 16..header + 20].copy_from_slice(&size.to_le_bytes());
+        data[header + 20..header + 24].copy_from_slice(&(SECTION_OFFSET as u32).to_le_bytes());
+        // IMAGE_SCN_CNT_INITIALIZED_DATA | IMAGE_SCN_MEM_READ | IMAGE_SCN_MEM_WRITE
+        data[header + 36..header + 40].copy_from_slice(&0xC000_0040u32.to_le_bytes());
+
+        data.extend_from_slice(&self.section);
+        data.resize(SECTION_OFFSET + size as usize, 0);
+
+        data
+    }
+}
@@ -1,11 +1,12 @@
 use std::{
-    fs::File,
+    fs::{self, File},
     iter,
     sync::mpsc::{self, SyncSender},
-    thread,
-    time::Duration,
+    thread::{self, JoinHandle},
 };
 
+use anyhow::anyhow;
+#[cfg(windows)]
 use anyhow::bail;
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::{
@@ -13,6 +14,9 @@ use ratatui::{
     symbols::border,
     widgets::{block::*, *},
 };
+#[cfg(windows)]
+use std::time::{Duration, Instant};
+#[cfg(windows)]
 use windows::{
     core::{s, PCSTR},
     Win32::UI::{
@@ -23,17 +27,28 @@ use windows::{
 
 use crate::{
     btd::{
+        connection::{Connection, ConnectionState},
         log::{GameLog, GameLogState},
         summary::{GameSummary, InGameSummary, Tower},
-        BloonsGame, BloonsHistogram,
+        BloonsGame, BloonsHistogram, GameTick,
     },
-    win32_util, Previous, Result,
+    memory::MemoryStatsReport,
+    Previous, Result,
 };
 
+use self::poller::{Poller, TickSubscriber};
+
+#[cfg(windows)]
+use crate::win32_util;
+
+mod poller;
 mod tui;
 
 enum AppEvent {
     Summary(GameSummary),
+    Connection(ConnectionState),
+    TornReads(String),
+    Error(String),
     Exit,
 }
 
@@ -51,10 +66,10 @@ impl InputThread {
             match event::read()? {
                 // it's important to check that the event is a key press event as
                 // crossterm also emits key release and repeat events on Windows.
-                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                    if key_event.code == KeyCode::Esc {
-                        self.out.send(AppEvent::Exit)?;
-                    }
+                Event::Key(key_event)
+                    if key_event.kind == KeyEventKind::Press && key_event.code == KeyCode::Esc =>
+                {
+                    self.out.send(AppEvent::Exit)?;
                 }
 
                 _ => {} // e => println!("{:?}", e),
@@ -63,183 +78,299 @@ impl InputThread {
     }
 }
 
-struct SummaryThread {
+/// Forwards summaries and how often collection reads are torn to the UI thread when they change
+struct SummaryView {
     out: SyncSender<AppEvent>,
-    game: BloonsGame,
+    previous: Previous<GameSummary>,
+    torn_reads: Previous<String>,
 }
 
-impl SummaryThread {
-    fn new(out: SyncSender<AppEvent>, game: BloonsGame) -> Self {
-        Self { out, game }
+impl SummaryView {
+    fn new(out: SyncSender<AppEvent>) -> Self {
+        Self {
+            out,
+            previous: Default::default(),
+            torn_reads: Default::default(),
+        }
     }
 
-    fn run(&mut self) -> Result<()> {
-        let mut previous = Previous::default();
-
-        loop {
-            let state = self.game.get_summary();
-
-            if let (GameSummary::InGame(a), Some(GameSummary::InGame(b))) =
-                (&state, &previous.value)
-            {
-                let do_beep = a
-                    .upgrades
-                    .iter()
-                    .filter(|up| match a.selected_index {
-                        Some(i) => i == up.tower_index,
-                        None => true,
-                    })
-                    .any(|upgrade| (b.cash..a.cash).contains(&upgrade.cost));
-
-                if do_beep {
-                    win32_util::beep();
-                }
+    fn publish(&mut self, summary: GameSummary) -> Result<()> {
+        if self.previous.set(summary.clone()) {
+            self.out.send(AppEvent::Summary(summary))?;
+        }
 
-                if should_pause(a) && !should_pause(b) {
-                    unsafe {
-                        let hwnd = FindWindowA(PCSTR::null(), s!("BloonsTD6-Epic"));
-
-                        while hwnd != GetForegroundWindow() {
-                            SetForegroundWindow(hwnd);
-                            SetCapture(hwnd);
-                            SetFocus(hwnd);
-                            SetActiveWindow(hwnd);
-                            thread::sleep(Duration::from_millis(1));
-                        }
-                    }
-
-                    while !self
-                        .game
-                        .get_ingame()?
-                        .unwrap()
-                        .stopped_clock_for_menu_open()?
-                    {
-                        win32_util::send_input(&win32_util::make_keypress_scancode(0x29));
-                        thread::sleep(Duration::from_millis(1));
-                    }
-                }
-            }
+        Ok(())
+    }
+}
 
-            if previous.set(state.clone()) {
-                self.out.send(AppEvent::Summary(state))?;
-            }
+impl TickSubscriber for SummaryView {
+    fn on_tick(&mut self, game: &BloonsGame, tick: &GameTick) -> Result<()> {
+        let torn_reads = torn_reads(&game.memory_stats());
 
-            thread::sleep(Duration::from_millis(25));
+        if self.torn_reads.set(torn_reads.clone()) {
+            self.out.send(AppEvent::TornReads(torn_reads))?;
         }
+
+        self.publish(GameSummary::InGame(tick.summary.clone()))
     }
-}
 
-struct GameLogThread {
-    out: SyncSender<AppEvent>,
-    game: BloonsGame,
-}
+    fn on_idle(&mut self, summary: &GameSummary) -> Result<()> {
+        self.publish(summary.clone())
+    }
 
-impl GameLogThread {
-    fn new(out: SyncSender<AppEvent>, game: BloonsGame) -> Self {
-        Self { out, game }
+    fn on_connection(&mut self, state: &ConnectionState) -> Result<()> {
+        self.out.send(AppEvent::Connection(state.clone()))?;
+
+        Ok(())
     }
 
-    fn run(&mut self) -> Result<()> {
-        let mut state = GameLogState::default();
-        let mut log = GameLog::default();
+    fn on_error(&mut self, subscriber: &str, error: &anyhow::Error) -> Result<()> {
+        self.out
+            .send(AppEvent::Error(format!("{}: {:#}", subscriber, error)))?;
 
-        loop {
-            std::thread::sleep(Duration::from_millis(100));
+        Ok(())
+    }
+}
 
-            if let Ok(new_state) = self.get_state() {
-                if new_state.towers.is_empty() {
-                    log = GameLog::default();
-                }
+/// the share of collection reads that caught the game mid-update since attaching, which should
+/// stay near zero; only the rate is shown so that it changes rarely enough not to redraw every tick
+fn torn_reads(stats: &MemoryStatsReport) -> String {
+    format!("{:.1}% of collection reads torn", stats.torn_rate())
+}
+
+/// Beeps when an upgrade becomes affordable and pauses when bloons get close to leaking
+#[derive(Default)]
+struct Alerts {
+    previous: Option<InGameSummary>,
+    /// pausing waits for the game to respond, so it runs on its own thread instead of the poller's
+    pausing: Option<JoinHandle<Result<()>>>,
+}
+
+impl Alerts {
+    /// the result of the last pause, once it's done
+    fn finish_pausing(&mut self) -> Result<()> {
+        match self.pausing.take_if(|pausing| pausing.is_finished()) {
+            Some(pausing) => pausing
+                .join()
+                .map_err(|_| anyhow!("pausing the game panicked"))?,
+            None => Ok(()),
+        }
+    }
+}
 
-                log.update(&state, &new_state);
-                state = new_state;
+impl TickSubscriber for Alerts {
+    fn on_tick(&mut self, game: &BloonsGame, tick: &GameTick) -> Result<()> {
+        let a = &tick.summary;
+
+        if let Some(b) = &self.previous {
+            let do_beep = a
+                .upgrades
+                .iter()
+                .filter(|up| match a.selected_index {
+                    Some(i) => i == up.tower_index,
+                    None => true,
+                })
+                .any(|upgrade| (b.cash..a.cash).contains(&upgrade.cost));
+
+            if do_beep {
+                beep();
+            }
 
-                let out = File::create(format!("log/{}.json", state.label))?;
-                serde_json::to_writer_pretty(out, &log)?;
+            if should_pause(a) && !should_pause(b) && self.pausing.is_none() {
+                let game = game.without_cache();
+                self.pausing = Some(thread::spawn(move || pause_game(&game)));
             }
         }
+
+        self.previous = Some(a.clone());
+
+        self.finish_pausing()
     }
 
-    fn get_state(&self) -> Result<GameLogState> {
-        let Some(ingame) = self.game.get_ingame()? else {
-            bail!("not ingame");
-        };
+    fn on_idle(&mut self, _summary: &GameSummary) -> Result<()> {
+        self.previous = None;
 
-        let simulation = ingame.unity_to_simulation()?.simulation()?;
+        Ok(())
+    }
+}
 
-        Ok(GameLogState::load(&simulation)?)
+/// Keeps a log of purchases for the current game in `log/<map> <mode>.json`
+#[derive(Default)]
+struct GameLogWriter {
+    state: GameLogState,
+    log: GameLog,
+}
+
+impl TickSubscriber for GameLogWriter {
+    fn on_tick(&mut self, _game: &BloonsGame, tick: &GameTick) -> Result<()> {
+        let new_state = &tick.log;
+
+        if new_state.towers.is_empty() {
+            self.log = GameLog::default();
+        }
+
+        let previous = self.log.clone();
+
+        self.log.update(&self.state, new_state);
+        self.state = new_state.clone();
+
+        if self.log != previous {
+            fs::create_dir_all("log")?;
+            let out = File::create(format!("log/{}.json", self.state.label))?;
+            serde_json::to_writer_pretty(out, &self.log)?;
+        }
+
+        Ok(())
     }
 }
 
-struct BloonsThread {
-    out: SyncSender<AppEvent>,
-    game: BloonsGame,
+/// Records where on the track bloons are seen
+struct BloonsTracker {
     histogram: BloonsHistogram,
 }
 
-impl BloonsThread {
-    fn new(out: SyncSender<AppEvent>, game: BloonsGame) -> Self {
+impl BloonsTracker {
+    fn new() -> Self {
         let histogram = BloonsHistogram::new(256);
 
-        Self {
-            out,
-            game,
-            histogram,
+        Self { histogram }
+    }
+}
+
+impl TickSubscriber for BloonsTracker {
+    fn on_tick(&mut self, _game: &BloonsGame, tick: &GameTick) -> Result<()> {
+        let info = &tick.bloons;
+
+        for bloon in info.bloons.iter() {
+            self.histogram.add_one(bloon.distance / info.max_path);
         }
+
+        Ok(())
     }
+}
 
-    fn run(&mut self) -> Result<()> {
-        loop {
-            match self.game.try_get_bloons() {
-                Err(e) => eprintln!("{:?}", e),
-                Ok(None) => continue,
-                Ok(Some(info)) => {
-                    for bloon in info.bloons {
-                        self.histogram.add_one(bloon.distance / info.max_path);
-                    }
-                }
+#[cfg(windows)]
+fn beep() {
+    win32_util::beep();
+}
+
+#[cfg(not(windows))]
+fn beep() {
+    use std::io::Write;
+
+    let mut out = std::io::stdout();
+    let _ = out.write_all(b"\x07").and_then(|_| out.flush());
+}
+
+/// how long to keep trying to focus the game and open the pause menu before giving up
+#[cfg(windows)]
+const PAUSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// focuses the game window and opens the pause menu
+#[cfg(windows)]
+fn pause_game(game: &BloonsGame) -> Result<()> {
+    let deadline = Instant::now() + PAUSE_TIMEOUT;
+
+    unsafe {
+        let hwnd = FindWindowA(PCSTR::null(), s!("BloonsTD6-Epic"));
+
+        while hwnd != GetForegroundWindow() {
+            if Instant::now() > deadline {
+                bail!("couldn't focus the game window");
             }
 
-            thread::sleep(Duration::from_millis(1000));
+            SetForegroundWindow(hwnd);
+            SetCapture(hwnd);
+            SetFocus(hwnd);
+            SetActiveWindow(hwnd);
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    // the flag has to be read again after each key press, not from the poll's cache
+    let game = game.without_cache();
+
+    loop {
+        let Some(ingame) = game.get_ingame()? else {
+            bail!("the match ended before it could be paused");
+        };
+
+        if ingame.stopped_clock_for_menu_open()? {
+            return Ok(());
+        }
+
+        if Instant::now() > deadline {
+            bail!("the pause menu didn't open");
         }
+
+        win32_util::send_input(&win32_util::make_keypress_scancode(0x29));
+        thread::sleep(Duration::from_millis(1));
     }
 }
 
+/// there is no way to focus or send input to a proton window from here, so only the alert is given
+#[cfg(not(windows))]
+fn pause_game(_game: &BloonsGame) -> Result<()> {
+    Ok(())
+}
+
 fn should_pause(summary: &InGameSummary) -> bool {
     summary.danger.is_some_and(|d| d < 50.0) && summary.mode != "Clicks"
 }
 
 #[derive(Debug)]
-pub struct App {}
+pub struct App {
+    connection: ConnectionState,
+    /// how often collection reads are torn, once the game has been read
+    torn_reads: Option<String>,
+    summary: GameSummary,
+    /// the last error from a part of the app that kept running after it
+    error: Option<String>,
+}
 
 impl App {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            connection: ConnectionState::Searching,
+            torn_reads: None,
+            summary: GameSummary::None("".into()),
+            error: None,
+        }
     }
 
     /// runs the application's main loop until the user quits
     pub fn run(&mut self) -> Result<()> {
-        let game = BloonsGame::find_game()?;
-
         let mut terminal = tui::init()?;
 
         let (send, recv) = mpsc::sync_channel(8);
 
-        let mut game_thread = SummaryThread::new(send.clone(), game.clone());
-        let mut log_thread = GameLogThread::new(send.clone(), game.clone());
+        let mut poller = Poller::new(Connection::new());
+        poller.subscribe(SummaryView::new(send.clone()));
+        poller.subscribe(Alerts::default());
+        poller.subscribe(GameLogWriter::default());
+        poller.subscribe(BloonsTracker::new());
 
         let input_thread = InputThread::new(send.clone());
 
-        thread::spawn(move || log_thread.run().unwrap());
-        thread::spawn(move || game_thread.run().unwrap());
+        thread::spawn(move || poller.run());
         thread::spawn(move || input_thread.run().unwrap());
 
+        self.render(&mut terminal)?;
+
         while let Ok(event) = recv.recv() {
             match event {
-                AppEvent::Summary(state) => self.render(&mut terminal, state)?,
+                AppEvent::Summary(summary) => self.summary = summary,
+                AppEvent::Connection(state) => {
+                    self.connection = state;
+                    self.torn_reads = None;
+                }
+                AppEvent::TornReads(torn_reads) => self.torn_reads = Some(torn_reads),
+                AppEvent::Error(error) => self.error = Some(error),
 
                 AppEvent::Exit => break,
             }
+
+            self.render(&mut terminal)?;
         }
 
         tui::restore()?;
@@ -247,33 +378,53 @@ impl App {
         Ok(())
     }
 
-    fn render(&self, terminal: &mut tui::Tui, summary: GameSummary) -> Result<()> {
-        terminal.draw(|frame| frame.render_widget(&summary, frame.size()))?;
+    fn render(&self, terminal: &mut tui::Tui) -> Result<()> {
+        terminal.draw(|frame| {
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(100), Constraint::Length(1)])
+                .split(frame.size());
+
+            frame.render_widget(&self.summary, layout[0]);
+
+            let status = Line::styled(self.status(), Style::new().dim());
+            frame.render_widget(status, layout[1]);
+        })?;
 
         Ok(())
     }
+
+    /// the connection, then how reads are going, then the last error
+    fn status(&self) -> String {
+        iter::once(self.connection.to_string())
+            .chain(self.torn_reads.clone())
+            .chain(self.error.clone())
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
 }
 
 impl Widget for &GameSummary {
     fn render(self, area: Rect, buf: &mut Buffer) {
         match self {
-            GameSummary::None(e) => {
-                let title = Title::from(" Not in game ".bold());
+            GameSummary::InGame(state) => {
+                state.render(area, buf);
+            }
+
+            summary => {
+                let (title, message) = summary.describe().unwrap();
+                let title = Title::from(format!(" {} ", title).bold());
 
                 let block = Block::default()
                     .title(title.alignment(Alignment::Center))
                     .borders(Borders::ALL)
                     .border_set(border::THICK);
 
-                let text = Text::raw(e);
+                let text = Text::raw(message);
                 text.render(block.inner(area), buf);
 
                 block.render(area, buf);
             }
-
-            GameSummary::InGame(state) => {
-                state.render(area, buf);
-            }
         }
     }
 }
@@ -297,13 +448,13 @@ fn render_towers_table(area: Rect, buf: &mut Buffer, state: &InGameSummary) {
                         "{}-{}-{}",
                         tower.tiers[0], tower.tiers[1], tower.tiers[2]
                     )),
-                    Text::raw(format!("{}", tower.name)),
+                    Text::raw(tower.name.clone()),
                 ]),
 
                 Tower::Hero(hero) => Row::new([
                     Text::raw(format!("${}", hero.worth)).alignment(Alignment::Right),
                     Text::raw(format!("{}", hero.level)).alignment(Alignment::Right),
-                    Text::raw(format!("{}", hero.name)),
+                    Text::raw(hero.name.clone()),
                 ]),
             };
 
@@ -343,7 +494,7 @@ fn render_upgrades_table(area: Rect, buf: &mut Buffer, state: &InGameSummary) {
             Row::new([
                 // Text::raw(format!("{}", upgrade.tower_index)),
                 Text::raw(format!("${}", upgrade.cost)).alignment(Alignment::Right),
-                Text::raw(format!("{}", upgrade.name)),
+                Text::raw(upgrade.name.clone()),
             ])
             .style(style)
         })
@@ -376,18 +527,15 @@ fn render_upgrades_table(area: Rect, buf: &mut Buffer, state: &InGameSummary) {
 }
 
 fn render_danger(area: Rect, buf: &mut Buffer, state: &InGameSummary) {
-    match state.danger {
-        Some(danger) => {
-            let width = area.width - 1;
+    if let Some(danger) = state.danger {
+        let width = area.width - 1;
 
-            let right = (danger / state.max_path * width as f32) as usize;
-            let left = width as usize - right;
+        let right = (danger / state.max_path * width as f32) as usize;
+        let left = width as usize - right;
 
-            let text = "-".repeat(left) + "O" + &" ".repeat(right);
+        let text = "-".repeat(left) + "O" + &" ".repeat(right);
 
-            Line::from(text).render(area, buf);
-        }
-        None => {}
+        Line::from(text).render(area, buf);
     }
 }
 
@@ -423,7 +571,7 @@ impl Widget for &InGameSummary {
         let danger_track =
             Block::default().borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT);
 
-        render_danger(danger_track.inner(layout[2]), buf, &self);
+        render_danger(danger_track.inner(layout[2]), buf, self);
         danger_track.render(layout[2], buf);
 
         let towers_table = Block::default()
@@ -434,7 +582,7 @@ impl Widget for &InGameSummary {
             })
             .borders(Borders::LEFT | Borders::TOP | Borders::BOTTOM);
 
-        render_towers_table(towers_table.inner(top[0]), buf, &self);
+        render_towers_table(towers_table.inner(top[0]), buf, self);
         towers_table.render(top[0], buf);
 
         let upgrades_table = Block::default()
@@ -447,7 +595,38 @@ impl Widget for &InGameSummary {
             })
             .borders(Borders::ALL);
 
-        render_upgrades_table(upgrades_table.inner(top[1]), buf, &self);
+        render_upgrades_table(upgrades_table.inner(top[1]), buf, self);
         upgrades_table.render(top[1], buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn shows_torn_reads_next_to_the_connection() {
+        let stats = MemoryStatsReport {
+            reads: 0,
+            hits: 0,
+            misses: 0,
+            source_reads: 0,
+            source_time: Duration::ZERO,
+            versioned_reads: 400,
+            torn_reads: 3,
+        };
+
+        let mut app = App::new();
+        assert_eq!(app.status(), "searching for BloonsTD6");
+
+        app.torn_reads = Some(torn_reads(&stats));
+        app.error = Some("GameLogWriter: log/ is missing".into());
+
+        assert_eq!(
+            app.status(),
+            "searching for BloonsTD6 | 0.8% of collection reads torn | GameLogWriter: log/ is missing"
+        );
+    }
+}
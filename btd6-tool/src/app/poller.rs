@@ -0,0 +1,228 @@
+use std::{thread, time::Duration};
+
+use crate::{
+    btd::{
+        connection::{Connection, ConnectionState},
+        summary::GameSummary,
+        BloonsGame, GameTick, TickKey,
+    },
+    memory::MemoryError,
+    Previous, Result,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Receives each simulation tick read by the `Poller`
+pub trait TickSubscriber: Send {
+    fn on_tick(&mut self, game: &BloonsGame, tick: &GameTick) -> Result<()>;
+
+    /// called instead of `on_tick` while there is no game to read, with the reason why
+    fn on_idle(&mut self, _summary: &GameSummary) -> Result<()> {
+        Ok(())
+    }
+
+    /// called when the game is found, lost or reattached
+    fn on_connection(&mut self, _state: &ConnectionState) -> Result<()> {
+        Ok(())
+    }
+
+    /// called when another subscriber fails, which keeps receiving ticks regardless
+    fn on_error(&mut self, _subscriber: &str, _error: &anyhow::Error) -> Result<()> {
+        Ok(())
+    }
+
+    /// the subscriber's name, for errors
+    fn name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+}
+
+/// Reads the game once per simulation tick and publishes the result to every subscriber
+pub struct Poller {
+    connection: Connection,
+    subscribers: Vec<Box<dyn TickSubscriber>>,
+    previous: Previous<TickKey>,
+}
+
+impl Poller {
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            subscribers: vec![],
+            previous: Default::default(),
+        }
+    }
+
+    pub fn subscribe(&mut self, subscriber: impl TickSubscriber + 'static) {
+        self.subscribers.push(Box::new(subscriber));
+    }
+
+    pub fn run(&mut self) -> ! {
+        loop {
+            if self.connection.update() {
+                self.previous = Default::default();
+
+                publish(&mut self.subscribers, |subscriber| {
+                    subscriber.on_connection(self.connection.state())
+                });
+            }
+
+            match self.connection.game() {
+                Some(game) => {
+                    game.invalidate();
+                    self.poll();
+                }
+
+                None => {
+                    let summary = match self.connection.state() {
+                        ConnectionState::Lost { .. } => GameSummary::ProcessGone,
+                        state => GameSummary::None(state.to_string()),
+                    };
+
+                    self.idle(summary);
+                }
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn poll(&mut self) {
+        let Some(game) = self.connection.game() else {
+            return;
+        };
+
+        let key = match game.try_get_tick_key() {
+            Ok(Some(key)) => key,
+            Ok(None) => return self.idle(GameSummary::NotInGame),
+            Err(e) => return self.fail(e),
+        };
+
+        if !self.previous.set(key) {
+            return;
+        }
+
+        match game.try_get_tick() {
+            Ok(Some(tick)) => {
+                publish(&mut self.subscribers, |subscriber| {
+                    subscriber.on_tick(game, &tick)
+                });
+            }
+
+            Ok(None) => self.idle(GameSummary::NotInGame),
+            Err(e) => self.fail(e),
+        }
+    }
+
+    /// a failed read may mean the game has exited, so check on it right away
+    fn fail(&mut self, error: anyhow::Error) {
+        // the game was mid-update, so keep showing the last tick and read this one again next poll
+        if let Some(MemoryError::Torn { .. }) = MemoryError::find(&error) {
+            self.previous = Default::default();
+            return;
+        }
+
+        self.connection.check_soon();
+
+        self.idle(GameSummary::from_error(&error))
+    }
+
+    fn idle(&mut self, summary: GameSummary) {
+        // read the next tick in full, even if its key matches the last one
+        self.previous = Default::default();
+
+        publish(&mut self.subscribers, |subscriber| {
+            subscriber.on_idle(&summary)
+        });
+    }
+}
+
+/// calls every subscriber, telling the others about any that fail rather than stopping, since
+/// one subscriber's errors (e.g. a log it can't write) shouldn't cut the rest off from the game
+fn publish(
+    subscribers: &mut [Box<dyn TickSubscriber>],
+    mut call: impl FnMut(&mut dyn TickSubscriber) -> Result<()>,
+) {
+    let mut errors = vec![];
+
+    for (index, subscriber) in subscribers.iter_mut().enumerate() {
+        if let Err(error) = call(subscriber.as_mut()) {
+            errors.push((index, subscriber.name(), error));
+        }
+    }
+
+    for (failed, name, error) in errors {
+        for (index, subscriber) in subscribers.iter_mut().enumerate() {
+            // an error reporting an error goes nowhere else
+            if index != failed {
+                let _ = subscriber.on_error(name, &error);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::bail;
+
+    use super::*;
+
+    struct Failing;
+
+    impl TickSubscriber for Failing {
+        fn on_tick(&mut self, _game: &BloonsGame, _tick: &GameTick) -> Result<()> {
+            Ok(())
+        }
+
+        fn on_idle(&mut self, _summary: &GameSummary) -> Result<()> {
+            bail!("log/ is missing")
+        }
+    }
+
+    /// records what it's called with
+    struct Recording(Arc<Mutex<Vec<String>>>);
+
+    impl TickSubscriber for Recording {
+        fn on_tick(&mut self, _game: &BloonsGame, _tick: &GameTick) -> Result<()> {
+            Ok(())
+        }
+
+        fn on_idle(&mut self, summary: &GameSummary) -> Result<()> {
+            self.0.lock().unwrap().push(format!("idle {:?}", summary));
+            Ok(())
+        }
+
+        fn on_error(&mut self, subscriber: &str, error: &anyhow::Error) -> Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{}: {}", subscriber, error));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn keeps_publishing_past_a_failing_subscriber() {
+        let calls = Arc::new(Mutex::new(vec![]));
+
+        let mut subscribers: Vec<Box<dyn TickSubscriber>> =
+            vec![Box::new(Failing), Box::new(Recording(calls.clone()))];
+
+        for _ in 0..2 {
+            publish(&mut subscribers, |s| s.on_idle(&GameSummary::NotInGame));
+        }
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            [
+                "idle NotInGame",
+                "Failing: log/ is missing",
+                "idle NotInGame",
+                "Failing: log/ is missing",
+            ]
+        );
+    }
+}
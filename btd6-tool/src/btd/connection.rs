@@ -0,0 +1,181 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::{memory::ProcessMemoryView, process::Process, Result};
+
+use super::{find_game_module, find_pid, open_process, BloonsGame};
+
+/// how often the process list is checked, both while searching and to notice a restart
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// limits how often failing reads can force a check
+const MIN_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// the game has not been found since the tool started
+    Searching,
+    Attached {
+        pid: u32,
+        module_base: u64,
+    },
+    /// the game we were attached to has exited
+    Lost {
+        reason: String,
+    },
+    /// the game is running again, but could not be opened yet
+    Reattaching {
+        pid: u32,
+        reason: String,
+    },
+}
+
+impl Display for ConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionState::Searching => write!(f, "searching for BloonsTD6"),
+            ConnectionState::Attached { pid, module_base } => {
+                write!(
+                    f,
+                    "attached to pid {} (GameAssembly.dll at {:#x})",
+                    pid, module_base
+                )
+            }
+            ConnectionState::Lost { reason } => write!(f, "lost game: {}", reason),
+            ConnectionState::Reattaching { pid, reason } => {
+                write!(f, "reattaching to pid {}: {}", pid, reason)
+            }
+        }
+    }
+}
+
+/// Keeps a `BloonsGame` attached to the running game across restarts
+pub struct Connection {
+    state: ConnectionState,
+    process: Option<Process>,
+    game: Option<BloonsGame>,
+    last_check: Option<Instant>,
+    check_soon: bool,
+}
+
+impl Connection {
+    pub fn new() -> Self {
+        Self {
+            state: ConnectionState::Searching,
+            process: None,
+            game: None,
+            last_check: None,
+            check_soon: false,
+        }
+    }
+
+    pub fn state(&self) -> &ConnectionState {
+        &self.state
+    }
+
+    /// the attached game, if there is one
+    pub fn game(&mut self) -> Option<&mut BloonsGame> {
+        self.game.as_mut()
+    }
+
+    /// makes the next `update` check the process, e.g. after reads start failing
+    pub fn check_soon(&mut self) {
+        self.check_soon = true;
+    }
+
+    /// checks whether the game has started, exited or moved, and returns true if the state changed
+    pub fn update(&mut self) -> bool {
+        let interval = match self.check_soon {
+            true => MIN_CHECK_INTERVAL,
+            false => CHECK_INTERVAL,
+        };
+
+        if self.last_check.is_some_and(|t| t.elapsed() < interval) {
+            return false;
+        }
+
+        self.last_check = Some(Instant::now());
+        self.check_soon = false;
+
+        let next = self.check();
+        if next == self.state {
+            return false;
+        }
+
+        if !matches!(next, ConnectionState::Attached { .. }) {
+            self.process = None;
+            self.game = None;
+        }
+
+        self.state = next;
+        true
+    }
+
+    fn check(&mut self) -> ConnectionState {
+        let pid = match find_pid() {
+            Ok(pid) => pid,
+            Err(e) => {
+                return match &self.state {
+                    ConnectionState::Searching => ConnectionState::Searching,
+                    ConnectionState::Lost { .. } => self.state.clone(),
+                    _ => ConnectionState::Lost {
+                        reason: e.to_string(),
+                    },
+                };
+            }
+        };
+
+        if let ConnectionState::Attached {
+            pid: attached_pid,
+            module_base,
+        } = self.state
+        {
+            if attached_pid == pid && self.get_module_base() == Some(module_base) {
+                return self.state.clone();
+            }
+        }
+
+        let retrying =
+            matches!(self.state, ConnectionState::Reattaching { pid: p, .. } if p == pid);
+
+        match self.attach(pid) {
+            Ok(module_base) => ConnectionState::Attached { pid, module_base },
+
+            // the game may still be loading GameAssembly.dll
+            Err(_) if self.state == ConnectionState::Searching => ConnectionState::Searching,
+
+            // still failing to reattach, so keep the first reason rather than reporting each retry's
+            Err(_) if retrying => self.state.clone(),
+
+            Err(e) => ConnectionState::Reattaching {
+                pid,
+                reason: e.to_string(),
+            },
+        }
+    }
+
+    fn get_module_base(&self) -> Option<u64> {
+        let process = self.process.as_ref()?;
+        let module = find_game_module(process).ok()?;
+
+        Some(module.get_bounds().ok()?.0)
+    }
+
+    fn attach(&mut self, pid: u32) -> Result<u64> {
+        // closes the handle to the old process, which the game holds one to as well
+        self.game = None;
+        self.process = None;
+
+        let process = open_process(pid)?;
+        let module_base = find_game_module(&process)?.get_bounds()?.0;
+
+        let memory = ProcessMemoryView::new(process.clone());
+
+        self.game = Some(BloonsGame::new(memory, module_base));
+        self.process = Some(process);
+
+        Ok(module_base)
+    }
+}
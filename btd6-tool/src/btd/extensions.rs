@@ -1,26 +1,106 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+};
+
 use anyhow::bail;
 
 use crate::{
-    memory::{ProcessMemoryView, TypeInfo},
-    Result,
+    memory::{
+        scan::{ModuleImage, Signature},
+        ProcessMemoryView, TypeInfo,
+    },
+    offsets, Result,
 };
 
 use super::types::*;
 
-// see extract.sh
-const TYPE_OFFSET_IN_GAME: u64 = 76052240;
+pub const IN_GAME_CLASS: &str = "Assets.Scripts.Unity.UI_New.InGame.InGame";
+
+// see extract.sh, used when offsets.json has no entry for the running build and scanning fails
+pub const TYPE_OFFSET_IN_GAME: u64 = 76052240;
+
+/// loads of a TypeInfo followed by its static fields, like the getter for `InGame.instance`
+const STATIC_ACCESS_SIGNATURES: &[(&str, usize, usize)] = &[
+    // mov rax, [rip+X]; mov rax, [rax+0xB8]
+    ("48 8B 05 ?? ?? ?? ?? 48 8B 80 B8 00 00 00", 3, 7),
+    // mov rax, [rip+X]; mov rcx, [rax+0xB8]
+    ("48 8B 05 ?? ?? ?? ?? 48 8B 88 B8 00 00 00", 3, 7),
+    // mov rcx, [rip+X]; mov rcx, [rcx+0xB8]
+    ("48 8B 0D ?? ?? ?? ?? 48 8B 89 B8 00 00 00", 3, 7),
+];
+
+/// the InGame TypeInfo slot found in each build
+static FOUND_TYPE_OFFSETS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
 
 impl InGame {
     pub fn get_instance(
         memory_view: &ProcessMemoryView,
         module_base: u64,
+        type_offset: u64,
     ) -> Result<Option<InGame>> {
-        let ingame_type: TypeInfo = memory_view.read(module_base + TYPE_OFFSET_IN_GAME)?;
+        let ingame_type: TypeInfo = memory_view.read(module_base + type_offset)?;
 
+        Self::from_type(&ingame_type)
+    }
+
+    /// reads the instance from the static fields of the InGame class
+    pub fn from_type(ingame_type: &TypeInfo) -> Result<Option<InGame>> {
         let ingame = ingame_type.get_statics()?.field(0x0)?;
 
         Ok(ingame)
     }
+
+    /// finds the offset of the InGame TypeInfo slot from the module base by scanning its code,
+    /// once for each build
+    pub fn find_type_offset(memory_view: &ProcessMemoryView, module_base: u64) -> Result<u64> {
+        let memory_view = memory_view.without_cache();
+        let image = ModuleImage::read(&memory_view, module_base)?;
+        let build_id = offsets::build_id(image.headers.timestamp);
+
+        if let Some(&offset) = FOUND_TYPE_OFFSETS.lock().unwrap().get(&build_id) {
+            return Ok(offset);
+        }
+
+        let signatures = STATIC_ACCESS_SIGNATURES
+            .iter()
+            .map(|&(pattern, displacement, len)| Signature::new(pattern, displacement, len))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut slots = BTreeSet::new();
+        for section in image.code_sections() {
+            let data = image.read_section(&memory_view, section)?;
+            let address = module_base + section.virtual_address as u64;
+
+            for signature in signatures.iter() {
+                slots.extend(
+                    signature
+                        .resolve_iter(&data, address)
+                        .filter(|&slot| image.is_data(slot)),
+                );
+            }
+        }
+
+        let (namespace, name) = IN_GAME_CLASS.rsplit_once('.').unwrap();
+
+        for slot in slots {
+            // slots of classes that haven't been initialized yet don't point to a class
+            let Ok(type_info) = memory_view.read::<TypeInfo>(slot) else {
+                continue;
+            };
+
+            if type_info.get_name().is_ok_and(|v| v == name)
+                && type_info.get_namespace().is_ok_and(|v| v == namespace)
+            {
+                let offset = slot - module_base;
+                FOUND_TYPE_OFFSETS.lock().unwrap().insert(build_id, offset);
+
+                return Ok(offset);
+            }
+        }
+
+        bail!("InGame TypeInfo not found")
+    }
 }
 
 impl Simulation {
@@ -88,7 +168,7 @@ impl GameModel {
         let mode = self.game_mode()?;
         let map_name = self.map()?.map_name()?;
 
-        assert_eq!("Standard", self.game_type()?.to_string());
+        assert_eq!("Standard", self.game_type()?.read_string()?);
 
         let identifier = format!("{} - {}", map_name, mode);
 
@@ -123,3 +203,63 @@ impl Tower {
         Ok(self.area_placed_on()? != ObjectId::INVALID)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        offsets::testing::IN_GAME_TYPE_OFFSET,
+        testing::{Heap, CODE, DATA, MODULE},
+    };
+
+    /// writes `mov <reg>, [rip+X]` followed by a load of the static fields, referencing `slot`
+    fn static_access(h: &mut Heap, address: u64, pattern: &str, slot: u64) {
+        let displacement = (slot as i64 - (address as i64 + 7)) as i32;
+
+        let mut code = pattern
+            .split_whitespace()
+            .map(|byte| u8::from_str_radix(byte, 16).unwrap_or(0))
+            .collect::<Vec<_>>();
+        code[3..7].copy_from_slice(&displacement.to_le_bytes());
+
+        h.write(address, &code);
+    }
+
+    #[test]
+    fn finds_the_in_game_type_info_slot_once_per_build() {
+        let mut h = Heap::new();
+        h.section(".data", 0x800, 0x1000, DATA);
+        h.section(".text", 0x2000, 0x1000, CODE);
+
+        let in_game = h.class_in("Assets.Scripts.Unity.UI_New.InGame", "InGame", None);
+        let other = h.class_in("Assets.Scripts.Unity.UI_New.InGame", "Other", None);
+        h.write_u64(MODULE + IN_GAME_TYPE_OFFSET, in_game);
+        // another class's slot comes first
+        h.write_u64(MODULE + 0x900, other);
+        // a code address holding a class isn't a TypeInfo slot
+        h.write_u64(MODULE + 0x2800, in_game);
+
+        let pattern = |i: usize| STATIC_ACCESS_SIGNATURES[i].0;
+        static_access(&mut h, MODULE + 0x2100, pattern(0), MODULE + 0x900);
+        static_access(&mut h, MODULE + 0x2200, pattern(1), MODULE + 0x2800);
+        static_access(
+            &mut h,
+            MODULE + 0x2300,
+            pattern(2),
+            MODULE + IN_GAME_TYPE_OFFSET,
+        );
+
+        let view = h.view();
+        assert_eq!(
+            InGame::find_type_offset(&view, MODULE).unwrap(),
+            IN_GAME_TYPE_OFFSET
+        );
+
+        // the same build isn't scanned again
+        h.write(MODULE + 0x2000, &[0; 0x1000]);
+        assert_eq!(
+            InGame::find_type_offset(&h.view(), MODULE).unwrap(),
+            IN_GAME_TYPE_OFFSET
+        );
+    }
+}
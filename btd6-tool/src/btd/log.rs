@@ -21,8 +21,8 @@ pub struct Tower {
 
 impl GameLogState {
     pub fn load(sim: &Simulation) -> Result<GameLogState> {
-        let map_name = sim.model()?.map()?.map_name()?.to_string();
-        let mode = sim.model()?.game_mode()?.to_string();
+        let map_name = sim.model()?.map()?.map_name()?.read_string()?;
+        let mode = sim.model()?.game_mode()?.read_string()?;
 
         let label = format!("{} {}", map_name, mode);
         let seed = sim.model()?.random_seed()?;
@@ -32,8 +32,8 @@ impl GameLogState {
 
         for tower in sim.map()?.towers()? {
             if tower.is_real()? {
-                let id = tower.id()?.to_string();
-                let base_id = tower.model()?.base_id()?.to_string();
+                let id = tower.id()?.read_string()?;
+                let base_id = tower.model()?.base_id()?.read_string()?;
 
                 let mut upgrades = HashSet::new();
 
@@ -104,7 +104,7 @@ impl GameLog {
         }
 
         for (tower_id, _) in a.towers.iter() {
-            if let None = b.towers.get(tower_id) {
+            if !b.towers.contains_key(tower_id) {
                 self.entries.push(LogEntry {
                     time: b.time,
                     data: LogData::SellTower {
@@ -136,3 +136,55 @@ pub enum LogData {
         tower_id: String,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Scene;
+
+    #[test]
+    fn loads_from_synthetic_heap() {
+        let mut scene = Scene::new();
+        let state = GameLogState::load(&scene.heap.get(scene.simulation)).unwrap();
+
+        assert_eq!(state.label, "Monkey Meadow Standard");
+        assert_eq!(state.seed, 42);
+        assert_eq!(state.time, 100);
+
+        let tower = Tower {
+            base_id: "DartMonkey".into(),
+            upgrades: HashSet::from(["Sharp Shots".into()]),
+        };
+        assert_eq!(state.towers, HashMap::from([("tower-1".into(), tower)]));
+
+        let mut log = GameLog::default();
+        log.update(&GameLogState::default(), &state);
+
+        assert_eq!(
+            log.entries,
+            [
+                LogEntry {
+                    time: 100,
+                    data: LogData::BuyTower {
+                        base_id: "DartMonkey".into(),
+                        tower_id: "tower-1".into(),
+                    },
+                },
+                LogEntry {
+                    time: 100,
+                    data: LogData::BuyUpgrade {
+                        tower_id: "tower-1".into(),
+                        upgrade_id: "Sharp Shots".into(),
+                    },
+                },
+            ]
+        );
+
+        // the log is rewound to the new time when the game is
+        scene.heap.write_u32(scene.time, 50);
+        let rewound = GameLogState::load(&scene.heap.get(scene.simulation)).unwrap();
+
+        log.update(&state, &rewound);
+        assert!(log.entries.is_empty());
+    }
+}
@@ -1,30 +1,40 @@
 #![allow(dead_code)]
 
-use std::collections::HashMap;
+use std::{collections::HashMap, fs::File, io::Read, path::Path, sync::Arc};
 
-use anyhow::bail;
+use anyhow::{bail, Context};
+#[cfg(windows)]
 use windows::Win32::System::Threading::{
     PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_VM_READ,
 };
 
 use crate::{
-    memory::ProcessMemoryView,
+    memory::{
+        minidump::{self, Minidump},
+        snapshot::{self, Snapshot, SnapshotRecorder},
+        ClassRegistry, MemoryStatsReport, Pointer, ProcessMemoryView, TypeInfo,
+    },
+    offsets::{self, BuildOffsets},
     process::{Module, Process},
     Previous, Result,
 };
 
+pub mod connection;
 pub mod extensions;
 pub mod log;
 pub mod summary;
 pub mod types;
 
 use self::{
+    extensions::{IN_GAME_CLASS, TYPE_OFFSET_IN_GAME},
+    log::GameLogState,
     summary::{GameSummary, InGameSummary},
     types::{
         BloonModel, BloonTargetProxy, GameModel, InGame, Tower, UpgradeModel, UpgradePathModel,
     },
 };
 
+#[cfg(windows)]
 pub fn find_pid() -> Result<u32> {
     for pid in Process::enum_process_ids() {
         if let Ok(process) = Process::from_pid(pid, PROCESS_QUERY_LIMITED_INFORMATION) {
@@ -39,6 +49,39 @@ pub fn find_pid() -> Result<u32> {
     bail!("bloons process not found")
 }
 
+#[cfg(target_os = "linux")]
+pub fn find_pid() -> Result<u32> {
+    for pid in Process::enum_process_ids() {
+        if is_bloons_process(pid) {
+            return Ok(pid);
+        }
+    }
+
+    bail!("bloons process not found")
+}
+
+/// wine rewrites argv[0] to the windows path of the executable and names the process after it,
+/// which other processes given the game's path as their first argument aren't
+#[cfg(target_os = "linux")]
+pub fn is_bloons_process(pid: u32) -> bool {
+    let Ok(args) = Process::get_command_line(pid) else {
+        return false;
+    };
+
+    args.first().is_some_and(|v| v.ends_with("BloonsTD6.exe"))
+        && Process::get_name(pid).is_ok_and(|name| name == "BloonsTD6.exe")
+}
+
+#[cfg(windows)]
+pub fn open_process(pid: u32) -> Result<Process> {
+    Process::from_pid(pid, PROCESS_QUERY_INFORMATION | PROCESS_VM_READ)
+}
+
+#[cfg(target_os = "linux")]
+pub fn open_process(pid: u32) -> Result<Process> {
+    Process::from_pid(pid)
+}
+
 pub fn find_game_module(process: &Process) -> Result<Module<'_>> {
     for module in process.get_modules()? {
         let module_name = module.get_base_name()?;
@@ -51,6 +94,28 @@ pub fn find_game_module(process: &Process) -> Result<Module<'_>> {
     bail!("module not found")
 }
 
+/// reads the build id of the loaded GameAssembly.dll
+pub fn get_build_id(memory: &ProcessMemoryView, module_base: u64) -> Result<String> {
+    let pe_offset: u32 = memory.read(module_base + 0x3c)?;
+    let pe_header = module_base + pe_offset as u64;
+
+    let signature: u32 = memory.read(pe_header)?;
+    if signature != 0x4550 {
+        bail!("invalid PE header")
+    }
+
+    let timestamp: u32 = memory.read(pe_header + 8)?;
+
+    Ok(offsets::build_id(timestamp))
+}
+
+/// switches field offsets to those of the loaded GameAssembly.dll's build
+fn select_offsets(memory: &ProcessMemoryView, module_base: u64) -> Option<Arc<BuildOffsets>> {
+    let build_id = get_build_id(memory, module_base).ok();
+
+    offsets::select(build_id.as_deref())
+}
+
 pub fn get_cash(ingame: &InGame) -> Result<u64> {
     Ok(ingame
         .unity_to_simulation()?
@@ -105,7 +170,7 @@ struct BloonModelCacheBuilder {
 
 impl BloonModelCacheBuilder {
     fn build(&mut self, raw: &BloonModel) -> Result<&BloonModelCacheEntry> {
-        let id = raw.id()?.to_string();
+        let id = raw.id()?.read_string()?;
 
         if !self.values.contains_key(&id) {
             let base_health = raw.max_health()? as u64;
@@ -166,11 +231,11 @@ pub struct BloonModelCacheEntry {
 impl BloonModelCacheEntry {
     pub fn worth(&self, round: u64) -> f32 {
         let multiplier = match round {
-            ..=50 => 1.0,
-            ..=60 => 0.5,
-            ..=85 => 0.2,
-            ..=100 => 0.1,
-            ..=120 => 0.05,
+            0..=50 => 1.0,
+            51..=60 => 0.5,
+            61..=85 => 0.2,
+            86..=100 => 0.1,
+            101..=120 => 0.05,
             _ => 0.02,
         };
 
@@ -190,7 +255,7 @@ impl UpgradeModelCache {
         for upgrade_model in model.upgrades()?.iter()? {
             let upgrade_model = upgrade_model?;
 
-            upgrades.insert(upgrade_model.name()?.to_string(), upgrade_model);
+            upgrades.insert(upgrade_model.name()?.read_string()?, upgrade_model);
         }
 
         Ok(Self { upgrades })
@@ -199,7 +264,7 @@ impl UpgradeModelCache {
     pub fn get_upgrade(&self, id: &UpgradePathModel) -> Result<&UpgradeModel> {
         Ok(self
             .upgrades
-            .get(&id.upgrade()?.to_string())
+            .get(&id.upgrade()?.read_string()?)
             .expect(&format!("upgrade not found: {}", id.upgrade()?)))
     }
 }
@@ -211,97 +276,193 @@ pub struct BloonsGame {
 
     memory: ProcessMemoryView,
     module_offset: u64,
+    ingame_type_offset: u64,
 }
 
 impl BloonsGame {
+    /// uses the offsets for the game's build, finding the InGame TypeInfo by scanning if they don't include it
     pub fn new(memory: ProcessMemoryView, module_offset: u64) -> Self {
+        let offsets = select_offsets(&memory, module_offset);
+
+        let ingame_type_offset = match offsets.and_then(|o| o.type_info.get(IN_GAME_CLASS).copied())
+        {
+            Some(offset) => offset,
+            None => InGame::find_type_offset(&memory, module_offset).unwrap_or(TYPE_OFFSET_IN_GAME),
+        };
+
+        let memory = match ClassRegistry::locate(&memory, module_offset) {
+            Ok(registry) => memory.with_registry(Arc::new(registry)),
+            Err(_) => memory,
+        };
+
+        Self::with_type_offset(memory, module_offset, ingame_type_offset)
+    }
+
+    fn with_type_offset(
+        memory: ProcessMemoryView,
+        module_offset: u64,
+        ingame_type_offset: u64,
+    ) -> Self {
         Self {
             ingame_addr: Default::default(),
             model_cache: Default::default(),
             memory,
             module_offset,
+            ingame_type_offset,
         }
     }
 
+    pub fn load_snapshot(path: impl AsRef<Path>) -> Result<Self> {
+        let snapshot = Snapshot::load(path)?;
+
+        // the snapshot's objects are laid out for the build it was recorded from, so reading them
+        // with another build's offsets would only give garbage
+        if !offsets::is_known(&snapshot.build_id) {
+            bail!(
+                "no offsets for build {}, which the snapshot was recorded from, run `btd6-tool offsets` for it",
+                snapshot.build_id
+            );
+        }
+
+        offsets::select(Some(&snapshot.build_id));
+
+        let memory = ProcessMemoryView::new(snapshot.memory);
+
+        Ok(Self::with_type_offset(
+            memory,
+            snapshot.module_base,
+            snapshot.ingame_type_offset,
+        ))
+    }
+
+    pub fn load_minidump(path: impl AsRef<Path>) -> Result<Self> {
+        let dump = Minidump::load(path)?;
+
+        let module_offset = dump
+            .find_module("GameAssembly.dll")
+            .context("GameAssembly.dll not in dump")?
+            .base;
+
+        Ok(Self::new(ProcessMemoryView::new(dump), module_offset))
+    }
+
+    /// opens a snapshot or minidump, based on the file's magic bytes
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Self> {
+        let mut magic = [0; 8];
+        File::open(&path)?.read_exact(&mut magic)?;
+
+        if magic.starts_with(minidump::MAGIC) {
+            Self::load_minidump(path)
+        } else if &magic == snapshot::MAGIC {
+            Self::load_snapshot(path)
+        } else {
+            bail!("unknown file format")
+        }
+    }
+
+    /// returns a copy of this game whose reads are captured by the returned recorder
+    pub fn record(&self) -> Result<(Self, Arc<SnapshotRecorder>)> {
+        let build_id = get_build_id(&self.memory, self.module_offset)?;
+
+        let recorder = Arc::new(SnapshotRecorder::new(
+            self.memory.clone(),
+            build_id,
+            self.module_offset,
+            self.ingame_type_offset,
+        ));
+
+        let memory = ProcessMemoryView::new(recorder.clone());
+
+        // keep the PE headers too, so the snapshot holds everything `new` reads
+        get_build_id(&memory, self.module_offset)?;
+
+        let game = Self::with_type_offset(memory, self.module_offset, self.ingame_type_offset);
+
+        Ok((game, recorder))
+    }
+
     pub fn find_game() -> Result<Self> {
         let pid = find_pid()?;
 
-        let process = Process::from_pid(pid, PROCESS_QUERY_INFORMATION | PROCESS_VM_READ)?;
+        let process = open_process(pid)?;
+        let module_offset = find_game_module(&process)?.get_bounds()?.0;
 
         let memory = ProcessMemoryView::new(process);
-        let module = find_game_module(&process)?;
 
-        Ok(Self::new(memory, module.get_bounds()?.0))
+        Ok(Self::new(memory, module_offset))
+    }
+
+    /// a copy of this game that reads straight from the memory source, for comparing against the cache
+    pub fn without_cache(&self) -> Self {
+        Self::with_type_offset(
+            self.memory.without_cache(),
+            self.module_offset,
+            self.ingame_type_offset,
+        )
+    }
+
+    /// drops memory cached during the previous poll
+    pub fn invalidate(&self) {
+        self.memory.invalidate();
+    }
+
+    pub fn memory_stats(&self) -> MemoryStatsReport {
+        self.memory.stats()
+    }
+
+    pub fn memory(&self) -> &ProcessMemoryView {
+        &self.memory
+    }
+
+    pub fn registry(&self) -> Option<&Arc<ClassRegistry>> {
+        self.memory.registry()
+    }
+
+    /// looks up a class by namespace and name, if the class registry was found
+    pub fn find_class(&self, namespace: &str, name: &str) -> Result<Option<TypeInfo>> {
+        let Some(registry) = self.registry() else {
+            return Ok(None);
+        };
+
+        // read through this game's view, so objects reached from the class are checked against the registry
+        let class = TypeInfo::find(registry, namespace, name)?.map(|class| {
+            TypeInfo(Pointer {
+                memory: self.memory.persistent(),
+                address: class.0.address,
+            })
+        });
+
+        Ok(class)
     }
 
     pub fn get_ingame(&self) -> Result<Option<InGame>> {
-        InGame::get_instance(&self.memory, self.module_offset)
+        let (namespace, name) = IN_GAME_CLASS.rsplit_once('.').unwrap();
+
+        match self.find_class(namespace, name)? {
+            Some(type_info) => InGame::from_type(&type_info),
+            None => InGame::get_instance(&self.memory, self.module_offset, self.ingame_type_offset),
+        }
     }
 
     pub fn try_get_bloons(&self) -> Result<Option<BloonsState>> {
         match self.get_ingame()? {
             None => Ok(None),
-            Some(ingame) => {
-                let mut bloons = vec![];
-                let mut max_path = 0.0f32;
-
-                for path in ingame
-                    .unity_to_simulation()?
-                    .simulation()?
-                    .map()?
-                    .path_manager()?
-                    .paths()?
-                    .iter()?
-                {
-                    let path = path?;
-
-                    for segment in path.segments()?.iter()? {
-                        let segment = segment?;
-
-                        max_path = max_path.max(segment.leak_distance()?);
-
-                        for bloon in segment.bloons()?.iter()? {
-                            let bloon = bloon?;
-
-                            bloons.push(Bloon::load(bloon)?);
-                        }
-                    }
-                }
-
-                Ok(Some(BloonsState::new(bloons, max_path)))
-            }
+            Some(ingame) => Ok(Some(BloonsState::load(&ingame)?)),
         }
     }
 
     pub fn get_summary(&mut self) -> GameSummary {
-        let state = match self.try_get_summary() {
-            Ok(s) => s,
-            Err(e) => {
-                let str = e.to_string() + "\n" + &e.backtrace().to_string();
-                GameSummary::None(str)
-            }
-        };
-
-        state
+        match self.try_get_summary() {
+            Ok(summary) => summary,
+            Err(e) => GameSummary::from_error(&e),
+        }
     }
 
     pub fn try_get_summary(&mut self) -> Result<GameSummary> {
         match self.get_ingame()? {
-            None => Ok(GameSummary::None("null game".into())),
+            None => Ok(GameSummary::NotInGame),
             Some(ingame) => {
-                if self.ingame_addr.set(ingame.0.address) {
-                    self.model_cache = None;
-                }
-
-                let model_cache = match self.model_cache.as_ref() {
-                    Some(m) => m,
-                    None => {
-                        self.model_cache = Some(UpgradeModelCache::load(
-                            &ingame.unity_to_simulation()?.simulation()?.model()?,
-                        )?);
-                        self.model_cache.as_ref().unwrap()
-                    }
-                };
+                let model_cache = self.get_model_cache(&ingame)?;
 
                 let state = InGameSummary::load(model_cache, &ingame)?;
 
@@ -309,6 +470,92 @@ impl BloonsGame {
             }
         }
     }
+
+    /// identifies the simulation tick the game is currently on
+    pub fn try_get_tick_key(&self) -> Result<Option<TickKey>> {
+        match self.get_ingame()? {
+            None => Ok(None),
+            Some(ingame) => Ok(Some(TickKey::load(&ingame)?)),
+        }
+    }
+
+    /// reads everything derived from the current simulation tick in one pass
+    pub fn try_get_tick(&mut self) -> Result<Option<GameTick>> {
+        let Some(ingame) = self.get_ingame()? else {
+            return Ok(None);
+        };
+
+        let key = TickKey::load(&ingame)?;
+
+        let model_cache = self.get_model_cache(&ingame)?;
+        let summary = InGameSummary::load(model_cache, &ingame)?;
+
+        let simulation = ingame.unity_to_simulation()?.simulation()?;
+        let log = GameLogState::load(&simulation)?;
+        let bloons = BloonsState::load(&ingame)?;
+
+        Ok(Some(GameTick {
+            key,
+            summary,
+            log,
+            bloons,
+        }))
+    }
+
+    fn get_model_cache(&mut self, ingame: &InGame) -> Result<&UpgradeModelCache> {
+        if self.ingame_addr.set(ingame.0.address) {
+            self.memory.invalidate_all();
+            self.model_cache = None;
+        }
+
+        if self.model_cache.is_none() {
+            self.model_cache = Some(UpgradeModelCache::load(
+                &ingame.unity_to_simulation()?.simulation()?.model()?,
+            )?);
+        }
+
+        Ok(self.model_cache.as_ref().unwrap())
+    }
+}
+
+/// The selected tower is part of the key, since selection changes while the game is paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickKey {
+    pub ingame: u64,
+    pub elapsed: i32,
+    pub selected: Option<u64>,
+}
+
+impl TickKey {
+    pub fn load(ingame: &InGame) -> Result<TickKey> {
+        let elapsed = ingame
+            .unity_to_simulation()?
+            .simulation()?
+            .time()?
+            .elapsed()?;
+
+        let selected = ingame
+            .player_contexts()?
+            .get(0)?
+            .input_manager()?
+            .selected()?
+            .map(|v| v.0.address);
+
+        Ok(Self {
+            ingame: ingame.0.address,
+            elapsed,
+            selected,
+        })
+    }
+}
+
+/// A consistent view of one simulation tick
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameTick {
+    pub key: TickKey,
+    pub summary: InGameSummary,
+    pub log: GameLogState,
+    pub bloons: BloonsState,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -321,6 +568,36 @@ impl BloonsState {
     pub fn new(bloons: Vec<Bloon>, max_path: f32) -> Self {
         Self { bloons, max_path }
     }
+
+    pub fn load(ingame: &InGame) -> Result<BloonsState> {
+        let mut bloons = vec![];
+        let mut max_path = 0.0f32;
+
+        for path in ingame
+            .unity_to_simulation()?
+            .simulation()?
+            .map()?
+            .path_manager()?
+            .paths()?
+            .iter()?
+        {
+            let path = path?;
+
+            for segment in path.segments()?.iter()? {
+                let segment = segment?;
+
+                max_path = max_path.max(segment.leak_distance()?);
+
+                for bloon in segment.bloons()?.iter()? {
+                    let bloon = bloon?;
+
+                    bloons.push(Bloon::load(bloon)?);
+                }
+            }
+        }
+
+        Ok(Self::new(bloons, max_path))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -331,7 +608,7 @@ pub struct Bloon {
 
 impl Bloon {
     fn load(bloon: BloonTargetProxy) -> Result<Bloon> {
-        let kind = bloon.bloon.model()?.base_id()?.to_string();
+        let kind = bloon.bloon.model()?.base_id()?.read_string()?;
         let distance = bloon.bloon.distance_travelled()?;
 
         Ok(Self { kind, distance })
@@ -366,3 +643,55 @@ impl BloonsHistogram {
         better as f32 / self.total as f32
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{offsets::testing::TIMESTAMP, testing::Scene};
+
+    #[test]
+    fn replays_a_recorded_snapshot() {
+        let scene = Scene::new();
+        let game = scene.heap.game();
+
+        let (mut recording, recorder) = game.record().unwrap();
+        let summary = recording.try_get_summary().unwrap();
+        assert!(matches!(summary, GameSummary::InGame(_)));
+
+        let path = std::env::temp_dir().join(format!("btd6-tool-{}.snap", std::process::id()));
+        recorder.snapshot().save(&path).unwrap();
+
+        let snapshot = Snapshot::load(&path).unwrap();
+        assert_eq!(snapshot.build_id, offsets::build_id(TIMESTAMP));
+        assert_eq!(snapshot.module_base, game.module_offset);
+
+        // the headers were recorded along with the objects the summary read
+        let memory = ProcessMemoryView::new(snapshot.memory);
+        assert_eq!(
+            get_build_id(&memory, game.module_offset).unwrap(),
+            snapshot.build_id
+        );
+
+        let mut replay = BloonsGame::load_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(replay.try_get_summary().unwrap(), summary);
+    }
+
+    #[test]
+    fn refuses_snapshots_from_unknown_builds() {
+        let scene = Scene::new();
+
+        let (_, recorder) = scene.heap.game().record().unwrap();
+        let mut snapshot = recorder.snapshot();
+        snapshot.build_id = "00000000".into();
+
+        let path = std::env::temp_dir().join(format!("btd6-tool-{}-old.snap", std::process::id()));
+        snapshot.save(&path).unwrap();
+
+        let error = BloonsGame::load_file(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(error.to_string().contains("no offsets for build 00000000"));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{memory::ObjectPointer, Result};
+use crate::{
+    memory::{FieldContext, MemoryError, ObjectPointer},
+    Result,
+};
 
 use super::{
     types::{self, TowerSet, TowerToSimulation},
@@ -7,10 +10,72 @@ use super::{
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum GameSummary {
+    /// the game is running, but not in a match
+    NotInGame,
+    /// a match is open, but its simulation hasn't been created yet
+    Loading,
+    /// the game's objects don't match the bindings, usually after a game update
+    BindingOutOfDate(String),
+    /// the game process has exited
+    ProcessGone,
+    /// an object the game was reading through is no longer mapped, usually as a match closes
+    Unreadable(String),
+    /// the game couldn't be read for any other reason
     None(String),
     InGame(InGameSummary),
 }
 
+/// (class, field) of the pointers that stay null while a match loads, from InGame to its simulation
+const LOADING_POINTERS: &[(&str, &str)] =
+    &[("InGame", "bridge"), ("UnityToSimulation", "simulation")];
+
+impl GameSummary {
+    /// explains why a summary couldn't be read, by the memory error it failed with
+    pub fn from_error(error: &anyhow::Error) -> GameSummary {
+        let field = error
+            .downcast_ref::<FieldContext>()
+            .map(|context| (context.class, context.field));
+
+        match MemoryError::find(error) {
+            Some(MemoryError::ProcessGone) => GameSummary::ProcessGone,
+
+            // the simulation is created as a match loads, so until then the pointers to it are null
+            Some(MemoryError::Null { .. })
+                if field.is_some_and(|field| LOADING_POINTERS.contains(&field)) =>
+            {
+                GameSummary::Loading
+            }
+
+            Some(MemoryError::TypeMismatch { .. }) => {
+                GameSummary::BindingOutOfDate(format!("{:#}", error))
+            }
+
+            Some(MemoryError::Unreadable { .. }) => GameSummary::Unreadable(format!("{:#}", error)),
+
+            _ => GameSummary::None(format!("{:#}", error)),
+        }
+    }
+
+    /// a title and message describing why there is no summary, or None if there is one
+    pub fn describe(&self) -> Option<(&str, String)> {
+        match self {
+            GameSummary::NotInGame => Some(("Not in game", "waiting for a match to start".into())),
+            GameSummary::Loading => Some(("Loading", "waiting for the match to load".into())),
+            GameSummary::BindingOutOfDate(e) => Some((
+                "Binding out of date",
+                format!(
+                    "the game may have updated, run `btd6-tool offsets` for the new build\n\n{}",
+                    e
+                ),
+            )),
+            GameSummary::ProcessGone => Some(("Game closed", "the game process has exited".into())),
+            GameSummary::Unreadable(e) => Some(("Can't read the game", e.clone())),
+            GameSummary::None(e) => Some(("Not in game", e.clone())),
+            GameSummary::InGame(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct InGameSummary {
     pub map_name: String,
@@ -32,9 +97,9 @@ impl InGameSummary {
 
         let sim = ingame.unity_to_simulation()?.simulation()?;
 
-        let map_name = sim.model()?.map()?.map_name()?.to_string();
+        let map_name = sim.model()?.map()?.map_name()?.read_string()?;
 
-        let mode = sim.model()?.game_mode()?.to_string();
+        let mode = sim.model()?.game_mode()?.read_string()?;
 
         let seed = sim.model()?.random_seed()?;
 
@@ -42,7 +107,7 @@ impl InGameSummary {
 
         for tower in sim.map()?.towers()? {
             if tower.is_real()? {
-                if tower.model()?.tower_set()? == TowerSet::HERO {
+                if tower.model()?.tower_set()? == TowerSet::Hero {
                     towers.push(Tower::Hero(Hero::load(&tower)?));
                 } else {
                     towers.push(Tower::Basic(BasicTower::load(&tower)?));
@@ -53,7 +118,7 @@ impl InGameSummary {
         let mut upgrades = vec![];
 
         for (tower, upgrade, _) in super::get_all_available_upgrades(model_cache, ingame)? {
-            let id = tower.id()?.to_string();
+            let id = tower.id()?.read_string()?;
 
             if let Some(index) = towers.iter().position(|t| match t {
                 Tower::Basic(t) => t.id == id,
@@ -63,11 +128,16 @@ impl InGameSummary {
             }
         }
 
-        let selected_index = match ingame.player_contexts()?.get(0)?.input_manager()?.selected()? {
+        let selected_index = match ingame
+            .player_contexts()?
+            .get(0)?
+            .input_manager()?
+            .selected()?
+        {
             None => None,
             Some(selected) => {
                 if let Ok(selected) = selected.cast::<TowerToSimulation>() {
-                    let id = selected.tower()?.id()?.to_string();
+                    let id = selected.tower()?.id()?.read_string()?;
 
                     towers.iter().position(|t| match t {
                         Tower::Basic(t) => t.id == id,
@@ -129,8 +199,8 @@ pub struct Hero {
 
 impl Hero {
     pub fn load(tower: &types::Tower) -> Result<Hero> {
-        let id = tower.id()?.to_string();
-        let name = tower.model()?.base_id()?.to_string();
+        let id = tower.id()?.read_string()?;
+        let name = tower.model()?.base_id()?.read_string()?;
 
         let level = tower.model()?.tier()? as u8;
         let worth = tower.worth()? as _;
@@ -154,8 +224,8 @@ pub struct BasicTower {
 
 impl BasicTower {
     pub fn load(tower: &types::Tower) -> Result<BasicTower> {
-        let id = tower.id()?.to_string();
-        let name = tower.model()?.base_id()?.to_string();
+        let id = tower.id()?.read_string()?;
+        let name = tower.model()?.base_id()?.read_string()?;
         let tiers = tower
             .model()?
             .tiers()?
@@ -190,7 +260,7 @@ impl Upgrade {
         let path = upgrade.path()?.try_into()?;
         let tier = upgrade.tier()?.try_into()?;
         let cost = upgrade.cost()?.try_into()?;
-        let name = upgrade.name()?.to_string();
+        let name = upgrade.name()?.read_string()?;
 
         Ok(Self {
             tower_index,
@@ -201,3 +271,83 @@ impl Upgrade {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Scene;
+
+    #[test]
+    fn loads_from_synthetic_heap() {
+        let scene = Scene::new();
+        let mut game = scene.heap.game();
+
+        let GameSummary::InGame(summary) = game.try_get_summary().unwrap() else {
+            panic!("not in game");
+        };
+
+        assert_eq!(summary.map_name, "Monkey Meadow");
+        assert_eq!(summary.mode, "Standard");
+        assert_eq!(summary.seed, 42);
+        assert_eq!(summary.cash, 650);
+        assert_eq!(summary.max_path, 100.0);
+        assert_eq!(summary.danger, None);
+        assert_eq!(summary.selected_index, None);
+
+        assert_eq!(
+            summary.towers,
+            [Tower::Basic(BasicTower {
+                id: "tower-1".into(),
+                name: "DartMonkey".into(),
+                tiers: [1, 0, 2],
+                worth: 350,
+            })]
+        );
+
+        assert_eq!(
+            summary.upgrades,
+            [Upgrade {
+                tower_index: 0,
+                path: 0,
+                tier: 2,
+                cost: 140,
+                name: "Sharp Shots".into(),
+            }]
+        );
+
+        // reading through the caches gives the same summary as reading directly
+        let uncached = game.without_cache().try_get_summary().unwrap();
+        assert_eq!(uncached, GameSummary::InGame(summary));
+    }
+
+    #[test]
+    fn loading_only_while_the_simulation_is_null() {
+        let mut scene = Scene::new();
+        let model = scene.heap.field(scene.simulation, "Simulation", "model");
+        scene.heap.write_u64(model, 0);
+
+        // a null pointer inside the simulation isn't the match loading
+        let error = scene.heap.game().try_get_summary().unwrap_err();
+        assert!(matches!(
+            MemoryError::find(&error),
+            Some(&MemoryError::Null { address, .. }) if address == model
+        ));
+        assert!(matches!(
+            GameSummary::from_error(&error),
+            GameSummary::None(_)
+        ));
+
+        scene
+            .heap
+            .set(scene.bridge, "UnityToSimulation", "simulation", 0);
+        assert_eq!(scene.heap.game().get_summary(), GameSummary::Loading);
+
+        scene
+            .heap
+            .set(scene.bridge, "UnityToSimulation", "simulation", 0xdead_0000);
+        assert!(matches!(
+            scene.heap.game().get_summary(),
+            GameSummary::Unreadable(_)
+        ));
+    }
+}
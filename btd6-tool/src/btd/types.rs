@@ -2,10 +2,14 @@
 
 use std::fmt::{Debug, Display};
 
-use bytemuck::cast_slice;
+use anyhow::{bail, Context};
+use bytemuck::cast_slice_mut;
 
 use crate::{
-    memory::{object_type, MemoryRead, Object, ObjectPointer, Pointer, ProcessMemoryView},
+    memory::{
+        object_type, sequential_layout, MemoryError, MemoryRead, Object, ObjectPointer, Pointer,
+        ProcessMemoryView,
+    },
     Result,
 };
 
@@ -15,61 +19,85 @@ macro_rules! field {
             unsafe { self.field($offset) }
         }
     };
+
+    ($name:ident: $field_type:ty ; $namespace:literal, $class:literal, $field:literal ; $versions:expr) => {
+        pub fn $name(&self) -> crate::Result<$field_type> {
+            static OFFSET: crate::offsets::FieldOffset = crate::offsets::FieldOffset::new(
+                $namespace,
+                $class,
+                $field,
+                stringify!($name),
+                $versions,
+            );
+            inventory::submit!(crate::offsets::FieldKey(&OFFSET));
+
+            let value = OFFSET
+                .get()
+                .and_then(|offset| unsafe { self.field(offset) });
+
+            value.with_context(|| crate::memory::FieldContext {
+                class: $class,
+                field: $field,
+                address: self.0.address,
+            })
+        }
+    };
 }
-pub(crate) use field;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, MemoryRead)]
 pub struct ObjectId(u32);
 
 impl ObjectId {
     pub const INVALID: ObjectId = ObjectId(4294967295);
 }
 
-impl MemoryRead for ObjectId {
-    const SIZE: usize = u32::SIZE;
-
-    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
-        Ok(Self(view.read(address)?))
-    }
-}
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, MemoryRead)]
 pub struct BloonTargetProxy {
     pub bloon: Bloon,
     pub segment: PathSegment,
 }
 
-impl MemoryRead for BloonTargetProxy {
-    const SIZE: usize = Bloon::SIZE + PathSegment::SIZE;
-
-    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
-        let bloon = view.read(address)?;
-        let segment = view.read(address + Bloon::SIZE as u64)?;
-
-        Ok(Self { bloon, segment })
-    }
+/// `Assets.Scripts.Simulation.SMath.Vector3`
+#[derive(Debug, Clone, Copy, PartialEq, MemoryRead)]
+pub struct Vector3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
 }
 
+/// longest string read before assuming the object was freed or isn't a string
+const MAX_STRING_LENGTH: usize = 0x10000;
+
 object_type!(CSharpString ; "String");
 impl CSharpString {
     pub fn len(&self) -> Result<usize> {
         Ok(unsafe { self.field::<u32>(0x0000)? as usize })
     }
-}
 
-impl Display for CSharpString {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let len = self.len().unwrap();
+    /// reads the string's UTF-16 contents, replacing any invalid code units
+    pub fn read_string(&self) -> Result<String> {
+        let memory = &self.0.memory;
+
+        memory.read_cached_string(self.0.address, || {
+            let len = self.len()?;
+            if len > MAX_STRING_LENGTH {
+                bail!("string at {:#x} has length {}", self.0.address, len);
+            }
 
-        let mut data = vec![0; 2 * len];
-        self.0
-            .memory
-            .read_exact(self.0.address + 0x0014, &mut data)
-            .unwrap();
+            let mut data = vec![0u16; len];
+            memory.read_exact(self.0.address + 0x0014, cast_slice_mut(&mut data))?;
 
-        let str = String::from_utf16(cast_slice(&data)).unwrap();
+            Ok(String::from_utf16_lossy(&data))
+        })
+    }
+}
 
-        Display::fmt(&str, f)
+impl Display for CSharpString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.read_string() {
+            Ok(value) => Display::fmt(&value, f),
+            Err(_) => write!(f, "<unreadable string at {:#x}>", self.0.address),
+        }
     }
 }
 
@@ -80,54 +108,342 @@ impl<T: MemoryRead> Array<T> {
     }
 
     pub fn get(&self, index: usize) -> Result<T> {
-        unsafe { Ok(self.field((0x0010 + T::SIZE * index) as u64)?) }
+        let len = self.len()?;
+        if index >= len {
+            bail!("index {} out of range for array of {}", index, len);
+        }
+
+        self.get_unchecked(index)
+    }
+
+    fn get_unchecked(&self, index: usize) -> Result<T> {
+        unsafe { self.field((0x0010 + T::SIZE * index) as u64) }
+    }
+
+    /// reads the first `len` elements, with the array's length read only once
+    fn read_prefix(&self, len: usize) -> Result<Vec<T>> {
+        if len > self.len()? {
+            bail!(MemoryError::Torn {
+                address: self.0.address
+            });
+        }
+
+        (0..len).map(|i| self.get_unchecked(i)).collect()
     }
 
     pub fn iter<'a>(&'a self) -> Result<impl Iterator<Item = Result<T>> + 'a> {
-        Ok((0..self.len()?).map(|i| self.get(i)))
+        let len = self.len()?;
+        Ok((0..len).map(|i| self.get_unchecked(i)))
     }
 }
 
 object_type!(List<T>);
 impl<T: MemoryRead> List<T> {
+    // private T[] _items; // 0x10
+    field!(0x0000 items: Array<T>);
+    // private int _size; // 0x18
+    field!(0x0008 size: u32);
+    // private int _version; // 0x1c
+    const VERSION: u64 = 0x0010 + 0x000c;
+
     pub fn len(&self) -> Result<usize> {
-        unsafe { Ok(self.field::<u32>(0x0008)? as usize) }
+        Ok(self.size()? as usize)
     }
 
     pub fn get(&self, index: usize) -> Result<T> {
-        let array: Array<T> = unsafe { self.field(0x0000)? };
+        let len = self.len()?;
+        if index >= len {
+            bail!("index {} out of range for list of {}", index, len);
+        }
 
-        Ok(array.get(index)?)
+        self.items()?.get(index)
+    }
+
+    /// reads every element, retrying if the game modifies the list meanwhile
+    pub fn snapshot(&self) -> Result<Vec<T>> {
+        let memory = &self.0.memory;
+
+        let items = memory.read_versioned(self.0.address + Self::VERSION, |uncached| {
+            let list = self.clone().rebind(uncached);
+            list.items()?.read_prefix(list.len()?)
+        })?;
+
+        Ok(items.into_iter().map(|item| item.rebind(memory)).collect())
     }
 
     pub fn iter(&self) -> Result<impl Iterator<Item = Result<T>>> {
-        let this = self.clone();
-        Ok((0..this.len()?).map(move |i| this.get(i)))
+        Ok(self.snapshot()?.into_iter().map(Ok))
     }
 }
 
+/// the offset of an entry of a Dictionary or HashSet, or None if it's free, which its negative
+/// hashCode marks
+fn used_entry(entries: &Object, size: usize, index: usize) -> Result<Option<u64>> {
+    let entry = (0x0010 + size * index) as u64;
+
+    let hash_code: i32 = unsafe { entries.field(entry)? };
+
+    Ok((hash_code >= 0).then_some(entry))
+}
+
+/// checks that an entries array holds the `count` entries its collection uses, since a shorter one
+/// was replaced by a resize
+fn check_entries(collection: &Pointer, entries: &Object, count: usize) -> Result<()> {
+    let len: u32 = unsafe { entries.field(0x0008)? };
+
+    if count > len as usize {
+        bail!(MemoryError::Torn {
+            address: collection.address
+        });
+    }
+
+    Ok(())
+}
+
+// Namespace: System.Collections.Generic
 object_type!(Dictionary<K, V>);
-impl<K: MemoryRead + Debug, V: MemoryRead + Debug> Dictionary<K, V> {
+impl<K: MemoryRead, V: MemoryRead> Dictionary<K, V> {
+    // private Dictionary.Entry<TKey, TValue>[] entries; // 0x18
+    field!(0x0008 entries: Option<Object>);
+    // private int count; // 0x20
+    field!(0x0010 count: u32);
+    // private int version; // 0x24
+    const VERSION: u64 = 0x0010 + 0x0014;
+    // private int freeCount; // 0x2c
+    field!(0x001c free_count: u32);
+
+    /// `int hashCode; int next; TKey key; TValue value;`
+    const LAYOUT: ([usize; 4], usize, usize) =
+        sequential_layout([(4, 4), (4, 4), (K::SIZE, K::ALIGN), (V::SIZE, V::ALIGN)]);
+
+    /// the number of entries, not counting removed ones
     pub fn len(&self) -> Result<usize> {
-        unsafe { Ok(self.field::<u32>(0x10)? as usize) }
+        Ok(self.count()?.saturating_sub(self.free_count()?) as usize)
     }
 
     pub fn get(&self, index: usize) -> Result<(K, V)> {
-        let entries: Object = unsafe { self.field(0x8)? };
-        assert_eq!("Entry[]", entries.get_type()?.get_name()?);
+        self.iter()?
+            .nth(index)
+            .context(format!("index {} out of range", index))?
+    }
+
+    /// the value stored for a key, found by comparing against every entry
+    pub fn get_value(&self, key: &K) -> Result<Option<V>>
+    where
+        K: PartialEq,
+    {
+        for entry in self.iter()? {
+            let (k, v) = entry?;
+            if k == *key {
+                return Ok(Some(v));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// reads every entry, retrying if the game modifies the dictionary meanwhile
+    pub fn snapshot(&self) -> Result<Vec<(K, V)>> {
+        let memory = &self.0.memory;
 
-        let key = unsafe { entries.field(0x18 + 0x18 * index as u64)? };
-        let value = unsafe { entries.field(0x20 + 0x18 * index as u64)? };
+        let entries = memory.read_versioned(self.0.address + Self::VERSION, |uncached| {
+            self.clone().rebind(uncached).read_entries()
+        })?;
 
-        Ok((key, value))
+        Ok(entries
+            .into_iter()
+            .map(|(key, value)| (key.rebind(memory), value.rebind(memory)))
+            .collect())
+    }
+
+    fn read_entries(&self) -> Result<Vec<(K, V)>> {
+        let ([_, _, key, value], size, _) = Self::LAYOUT;
+
+        let count = self.count()? as usize;
+        let Some(entries) = self.entries()? else {
+            return Ok(vec![]);
+        };
+
+        check_entries(&self.0, &entries, count)?;
+
+        let mut values = vec![];
+        for index in 0..count {
+            let Some(entry) = used_entry(&entries, size, index)? else {
+                continue;
+            };
+
+            let key = unsafe { entries.field(entry + key as u64)? };
+            let value = unsafe { entries.field(entry + value as u64)? };
+            values.push((key, value));
+        }
+
+        Ok(values)
     }
 
     pub fn iter(&self) -> Result<impl Iterator<Item = Result<(K, V)>>> {
+        Ok(self.snapshot()?.into_iter().map(Ok))
+    }
+}
+
+// Namespace: System.Collections.Generic
+object_type!(HashSet<T>);
+impl<T: MemoryRead> HashSet<T> {
+    // private HashSet.Slot<T>[] _slots; // 0x18
+    field!(0x0008 slots: Option<Object>);
+    // private int _count; // 0x20
+    field!(0x0010 count: u32);
+    // private int _lastIndex; // 0x24
+    field!(0x0014 last_index: u32);
+
+    /// `int hashCode; int next; T value;`
+    const LAYOUT: ([usize; 3], usize, usize) =
+        sequential_layout([(4, 4), (4, 4), (T::SIZE, T::ALIGN)]);
+
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.count()? as usize)
+    }
+
+    pub fn contains(&self, value: &T) -> Result<bool>
+    where
+        T: PartialEq,
+    {
+        for v in self.iter()? {
+            if v? == *value {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    pub fn iter(&self) -> Result<impl Iterator<Item = Result<T>>> {
+        let ([_, _, value], size, _) = Self::LAYOUT;
+
+        let last_index = self.last_index()? as usize;
+        let Some(slots) = self.slots()? else {
+            return Ok(vec![].into_iter());
+        };
+
+        check_entries(&self.0, &slots, last_index)?;
+
+        let mut values = vec![];
+        for index in 0..last_index {
+            let Some(slot) = used_entry(&slots, size, index)? else {
+                continue;
+            };
+
+            values.push(Ok(unsafe { slots.field(slot + value as u64)? }));
+        }
+
+        Ok(values.into_iter())
+    }
+}
+
+// Namespace: System.Collections.Generic
+object_type!(Queue<T>);
+impl<T: MemoryRead> Queue<T> {
+    // private T[] _array; // 0x10
+    field!(0x0000 array: Array<T>);
+    // private int _head; // 0x18
+    field!(0x0008 head: u32);
+    // private int _size; // 0x20
+    field!(0x0010 size: u32);
+
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.size()? as usize)
+    }
+
+    /// the item `index` places from the front of the queue
+    pub fn get(&self, index: usize) -> Result<T> {
+        let len = self.len()?;
+        if index >= len {
+            bail!("index {} out of range for queue of {}", index, len);
+        }
+
+        // a queue holding items can't have an empty array, unless it was replaced meanwhile
+        let array = self.array()?;
+        let capacity = array.len()?;
+        if capacity < len {
+            bail!(MemoryError::Torn {
+                address: self.0.address
+            });
+        }
+
+        array.get((self.head()? as usize + index) % capacity)
+    }
+
+    /// items from the front of the queue to the back
+    pub fn iter(&self) -> Result<impl Iterator<Item = Result<T>>> {
         let this = self.clone();
         Ok((0..this.len()?).map(move |i| this.get(i)))
     }
 }
 
+// Namespace: System.Collections.Generic
+object_type!(Stack<T>);
+impl<T: MemoryRead> Stack<T> {
+    // private T[] _array; // 0x10
+    field!(0x0000 array: Array<T>);
+    // private int _size; // 0x18
+    field!(0x0008 size: u32);
+
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.size()? as usize)
+    }
+
+    /// the item `index` places from the top of the stack
+    pub fn get(&self, index: usize) -> Result<T> {
+        let len = self.len()?;
+        if index >= len {
+            bail!("index {} out of range", index);
+        }
+
+        self.array()?.get(len - 1 - index)
+    }
+
+    /// items from the top of the stack to the bottom
+    pub fn iter(&self) -> Result<impl Iterator<Item = Result<T>>> {
+        let this = self.clone();
+        Ok((0..this.len()?).map(move |i| this.get(i)))
+    }
+}
+
+// Namespace: System.Collections.Generic
+object_type!(LinkedList<T>);
+impl<T: MemoryRead> LinkedList<T> {
+    // internal LinkedListNode<T> head; // 0x10
+    field!(0x0000 head: Option<LinkedListNode<T>>);
+    // internal int count; // 0x18
+    field!(0x0008 count: u32);
+
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.count()? as usize)
+    }
+
+    /// items from the head of the list, which is circular so it stops after `len` nodes
+    pub fn iter(&self) -> Result<impl Iterator<Item = Result<T>>> {
+        let mut node = self.head()?;
+
+        Ok((0..self.len()?).map(move |_| {
+            let current = node.take().context("linked list shorter than its count")?;
+            node = current.next()?;
+
+            current.item()
+        }))
+    }
+}
+
+// Namespace: System.Collections.Generic
+object_type!(LinkedListNode<T>);
+impl<T: MemoryRead> LinkedListNode<T> {
+    // internal LinkedListNode<T> next; // 0x18
+    field!(0x0008 next: Option<LinkedListNode<T>>);
+    // internal LinkedListNode<T> prev; // 0x20
+    field!(0x0010 prev: Option<LinkedListNode<T>>);
+    // internal T item; // 0x28
+    field!(0x0018 item: T);
+}
+
 object_type!(CashManager);
 impl CashManager {
     // public readonly KonFuze cash; // 0x10
@@ -141,17 +457,47 @@ impl<T: MemoryRead> LockList<T> {
     field!(0x0000 list: List<T>);
 }
 
-// Namespace: Assets.Scripts.Models.TowerSets
-pub struct TowerSet;
-impl TowerSet {
-    pub const NONE: u32 = 0;
-    pub const PRIMARY: u32 = 1;
-    pub const MILITARY: u32 = 2;
-    pub const MAGIC: u32 = 4;
-    pub const SUPPORT: u32 = 8;
-    pub const HERO: u32 = 16;
-    pub const PARAGON: u32 = 32;
-    pub const ITEMS: u32 = 64;
+#[btd6_tool_bindgen::enumeration(namespace = "Assets.Scripts.Models.TowerSets", flags)]
+pub enum TowerSet {
+    None = 0,
+    Primary = 1,
+    Military = 2,
+    Magic = 4,
+    Support = 8,
+    Hero = 16,
+    Paragon = 32,
+    Items = 64,
+}
+
+#[btd6_tool_bindgen::enumeration(namespace = "Assets.Scripts.Models.Bloons", flags)]
+pub enum BloonProperties {
+    None = 0,
+    Lead = 1,
+    Black = 2,
+    White = 4,
+    Purple = 8,
+    Frozen = 16,
+}
+
+#[btd6_tool_bindgen::enumeration(namespace = "Assets.Scripts.Models.GenericBehaviors")]
+pub enum DisplayCategory {
+    Default = 0,
+}
+
+#[btd6_tool_bindgen::enumeration(namespace = "Assets.Scripts.Models.Map")]
+pub enum MapDifficulty {
+    Beginner = 0,
+    Intermediate = 1,
+    Advanced = 2,
+    Expert = 3,
+}
+
+#[btd6_tool_bindgen::enumeration(namespace = "Assets.Scripts.Models.Towers")]
+pub enum TargetType {
+    First = 0,
+    Last = 1,
+    Close = 2,
+    Strong = 3,
 }
 
 #[btd6_tool_bindgen::class(rename = "PlayerContext.Context", namespace = "")]
@@ -206,7 +552,10 @@ pub struct Ability {
     cooldown_remaining: f32,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Towers.Behaviors.Abilities")]
+#[btd6_tool_bindgen::class(
+    namespace = "Assets.Scripts.Models.Towers.Behaviors.Abilities",
+    immutable
+)]
 pub struct AbilityModel {
     #[rename = "displayName"]
     name: CSharpString,
@@ -322,13 +671,13 @@ pub struct Spawner {
 #[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Simulation.Track")]
 pub struct RoundData {}
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Map")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Map", immutable)]
 pub struct PathModel {}
 
 #[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Simulation.Behaviors", base = RootBehavior)]
 pub struct DisplayBehavior {
     #[rename = "DisplayCategory"]
-    display_category: u32,
+    display_category: DisplayCategory,
     #[rename = "processing"]
     processing: bool,
 }
@@ -340,15 +689,19 @@ pub struct Tower {
     #[rename = "worth"]
     worth: f32,
     #[rename = "damageDealt"]
-    damage_dealt: u64,
+    damage_dealt: i64,
     #[rename = "cashEarned"]
-    cash_earned: u64,
+    cash_earned: i64,
     #[rename = "appliedCash"]
     applied_cash: f32,
     #[rename = "towerModel"]
     model: TowerModel,
     #[rename = "areaPlacedOn"]
     area_placed_on: ObjectId,
+    #[rename = "targetType"]
+    target_type: TargetType,
+    #[rename = "position"]
+    position: Vector3,
 }
 
 #[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Simulation.Bloons")]
@@ -357,15 +710,17 @@ pub struct Bloon {
     model: BloonModel,
     #[rename = "distanceTraveled"]
     distance_travelled: f32,
+    #[rename = "position"]
+    position: Vector3,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models", immutable)]
 pub struct Model {
     #[rename = "_name"]
     name: CSharpString,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models", immutable)]
 pub struct GameModel {
     #[rename = "difficultyId"]
     difficulty_id: CSharpString,
@@ -391,15 +746,15 @@ pub struct GameModel {
     bloons: Array<BloonModel>,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Map")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Map", immutable)]
 pub struct MapModel {
     #[rename = "mapDifficulty"]
-    map_difficulty: i32,
+    map_difficulty: MapDifficulty,
     #[rename = "mapName"]
     map_name: CSharpString,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Rounds")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Rounds", immutable)]
 pub struct RoundModel {
     #[rename = "groups"]
     groups: Array<BloonGroupModel>,
@@ -407,13 +762,13 @@ pub struct RoundModel {
     emissions: Option<Array<BloonEmissionModel>>,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Rounds")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Rounds", immutable)]
 pub struct RoundSetModel {
     #[rename = "rounds"]
     rounds: Array<RoundModel>,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Rounds")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Rounds", immutable)]
 pub struct BloonGroupModel {
     #[rename = "bloon"]
     bloon: CSharpString,
@@ -425,7 +780,7 @@ pub struct BloonGroupModel {
     count: i32,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Rounds")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Rounds", immutable)]
 pub struct BloonEmissionModel {
     #[rename = "bloon"]
     bloon: CSharpString,
@@ -436,19 +791,19 @@ pub struct BloonEmissionModel {
     #[rename = "isCustomBossEmission"]
     is_custom_boss_emission: bool,
     #[rename = "towerSetImmunity"]
-    tower_set_immunity: u32,
+    tower_set_immunity: TowerSet,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Rounds")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Rounds", immutable)]
 pub struct IncomeSetModel {}
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Entities")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Entities", immutable)]
 pub struct EntityModel {
     #[rename = "baseId"]
     base_id: CSharpString,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Bloons", base = Model)]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Bloons", base = Model, immutable)]
 pub struct BloonModel {
     #[rename = "id"]
     id: CSharpString,
@@ -460,18 +815,20 @@ pub struct BloonModel {
     leak_damage: f32,
     #[rename = "layerNumber"]
     layer_number: i32,
+    #[rename = "bloonProperties"]
+    properties: BloonProperties,
     #[rename = "childBloonModels"]
     children: List<BloonModel>,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Towers", base = EntityModel)]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Towers", base = EntityModel, immutable)]
 pub struct TowerModel {
     #[rename = "tier"]
-    tier: u32,
+    tier: i32,
     #[rename = "tiers"]
-    tiers: Array<u32>,
+    tiers: Array<i32>,
     #[rename = "towerSet"]
-    tower_set: u32,
+    tower_set: TowerSet,
     #[rename = "upgrades"]
     upgrades: Array<UpgradePathModel>,
     #[rename = "appliedUpgrades"]
@@ -480,7 +837,7 @@ pub struct TowerModel {
     is_bakable: bool,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Towers.Upgrades", base = Model)]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Towers.Upgrades", base = Model, immutable)]
 pub struct UpgradeModel {
     #[rename = "cost"]
     cost: i32,
@@ -494,7 +851,7 @@ pub struct UpgradeModel {
     locked: i32,
 }
 
-#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Towers.Upgrades")]
+#[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Models.Towers.Upgrades", immutable)]
 pub struct UpgradePathModel {
     #[rename = "tower"]
     tower: CSharpString,
@@ -510,3 +867,222 @@ pub struct KonFuze {
 
 #[btd6_tool_bindgen::class(namespace = "Assets.Scripts.Utils", base = KonFuze)]
 pub struct KonFuze_NoShuffle {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Heap;
+
+    /// an array of u64s with room for `capacity`, the first of which are `items`
+    fn array(h: &mut Heap, capacity: u64, items: &[u64]) -> u64 {
+        let array = h.array_of("UInt64", 8, capacity);
+
+        for (i, &item) in items.iter().enumerate() {
+            h.write_u64(array + 0x20 + 8 * i as u64, item);
+        }
+
+        array
+    }
+
+    /// a Dictionary or HashSet entries array, with a negative hashCode marking free entries
+    fn entries(h: &mut Heap, stride: u64, entries: &[Option<&[u64]>]) -> u64 {
+        let array = h.array_of("Entry", stride, entries.len() as u64);
+
+        for (i, entry) in entries.iter().enumerate() {
+            let address = array + 0x20 + stride * i as u64;
+
+            match entry {
+                Some(values) => {
+                    h.write_u32(address, i as u32);
+                    for (j, &value) in values.iter().enumerate() {
+                        h.write_u64(address + 8 + 8 * j as u64, value);
+                    }
+                }
+                None => h.write_u32(address, u32::MAX),
+            }
+        }
+
+        array
+    }
+
+    /// writes vectors one after another from `address`
+    fn vectors(h: &mut Heap, address: u64, vectors: &[[f32; 3]]) {
+        let bytes = vectors
+            .iter()
+            .flatten()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        h.write(address, &bytes);
+    }
+
+    #[test]
+    fn lays_out_value_types_sequentially() {
+        assert_eq!(f64::SIZE, 8);
+        assert_eq!(
+            sequential_layout([(1, 1), (8, 8), (4, 4)]),
+            ([0, 8, 16], 24, 8)
+        );
+
+        // aligned to the largest field rather than the size
+        assert_eq!((Vector3::SIZE, Vector3::ALIGN), (12, 4));
+        assert_eq!(Dictionary::<i32, Vector3>::LAYOUT, ([0, 4, 8, 12], 24, 4));
+        assert_eq!(Dictionary::<i32, f64>::LAYOUT, ([0, 4, 8, 16], 24, 8));
+        assert_eq!(HashSet::<Vector3>::LAYOUT, ([0, 4, 8], 20, 4));
+    }
+
+    #[test]
+    fn reads_value_types_inline() {
+        let mut h = Heap::new();
+
+        let array = h.array_of("Vector3", 12, 2);
+        vectors(&mut h, array + 0x20, &[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let positions: Array<Vector3> = h.get(array);
+        assert_eq!(
+            positions.get(1).unwrap(),
+            Vector3 {
+                x: 4.0,
+                y: 5.0,
+                z: 6.0
+            }
+        );
+
+        let tower = h.object("Tower", None);
+        let position = h.field(tower, "Tower", "position");
+        vectors(&mut h, position, &[[120.5, -30.0, 0.0]]);
+        let tower: Tower = h.get(tower);
+        assert_eq!(
+            tower.position().unwrap(),
+            Vector3 {
+                x: 120.5,
+                y: -30.0,
+                z: 0.0
+            }
+        );
+    }
+
+    #[test]
+    fn reads_dictionaries_without_free_entries() {
+        let mut h = Heap::new();
+        let array = entries(&mut h, 0x18, &[Some(&[1, 10]), None, Some(&[3, 30])]);
+
+        let address = h.object("Dictionary`2", None);
+        h.write_u64(address + 0x18, array);
+        h.write_u32(address + 0x20, 3);
+        h.write_u32(address + 0x2c, 1);
+
+        let dictionary: Dictionary<u64, u64> = h.get(address);
+        assert_eq!(dictionary.len().unwrap(), 2);
+        assert_eq!(dictionary.snapshot().unwrap(), [(1, 10), (3, 30)]);
+        assert_eq!(dictionary.get(1).unwrap(), (3, 30));
+        assert_eq!(dictionary.get_value(&3).unwrap(), Some(30));
+        assert_eq!(dictionary.get_value(&2).unwrap(), None);
+        assert!(dictionary.get(2).is_err());
+
+        // a count past the end of the entries means they were replaced
+        h.write_u32(address + 0x20, 4);
+        let dictionary: Dictionary<u64, u64> = h.get(address);
+        assert!(dictionary.snapshot().is_err());
+    }
+
+    #[test]
+    fn reads_hash_sets_without_free_slots() {
+        let mut h = Heap::new();
+        let slots = entries(&mut h, 0x10, &[Some(&[5]), None, Some(&[7])]);
+
+        let address = h.object("HashSet`1", None);
+        h.write_u64(address + 0x18, slots);
+        h.write_u32(address + 0x20, 2);
+        h.write_u32(address + 0x24, 3);
+
+        let set: HashSet<u64> = h.get(address);
+        assert_eq!(set.len().unwrap(), 2);
+        assert_eq!(
+            set.iter().unwrap().collect::<Result<Vec<_>>>().unwrap(),
+            [5, 7]
+        );
+        assert!(set.contains(&7).unwrap());
+        assert!(!set.contains(&6).unwrap());
+
+        h.write_u32(address + 0x24, 4);
+        let set: HashSet<u64> = h.get(address);
+        assert!(set.iter().is_err());
+    }
+
+    #[test]
+    fn reads_queues_from_their_head() {
+        let mut h = Heap::new();
+        let array = array(&mut h, 4, &[3, 4, 0, 1]);
+
+        // wraps around from the last element
+        let address = h.object("Queue`1", None);
+        h.write_u64(address + 0x10, array);
+        h.write_u32(address + 0x18, 3);
+        h.write_u32(address + 0x20, 3);
+
+        let queue: Queue<u64> = h.get(address);
+        assert_eq!(
+            queue.iter().unwrap().collect::<Result<Vec<_>>>().unwrap(),
+            [1, 3, 4]
+        );
+        assert!(queue.get(3).is_err());
+
+        // an empty array can't hold the queue's items
+        let empty = h.array_of("UInt64", 8, 0);
+        h.write_u64(address + 0x10, empty);
+        let queue: Queue<u64> = h.get(address);
+        assert!(queue.get(0).is_err());
+
+        h.write_u32(address + 0x20, 0);
+        let queue: Queue<u64> = h.get(address);
+        assert_eq!(queue.iter().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn reads_stacks_from_the_top() {
+        let mut h = Heap::new();
+        let array = array(&mut h, 4, &[1, 2, 3]);
+
+        let address = h.object("Stack`1", None);
+        h.write_u64(address + 0x10, array);
+        h.write_u32(address + 0x18, 3);
+
+        let stack: Stack<u64> = h.get(address);
+        assert_eq!(
+            stack.iter().unwrap().collect::<Result<Vec<_>>>().unwrap(),
+            [3, 2, 1]
+        );
+        assert!(stack.get(3).is_err());
+    }
+
+    #[test]
+    fn reads_linked_lists_around_once() {
+        let mut h = Heap::new();
+
+        let nodes = [10, 20, 30].map(|item| {
+            let node = h.object("LinkedListNode`1", None);
+            h.write_u64(node + 0x28, item);
+            node
+        });
+
+        for (i, &node) in nodes.iter().enumerate() {
+            h.write_u64(node + 0x18, nodes[(i + 1) % 3]);
+            h.write_u64(node + 0x20, nodes[(i + 2) % 3]);
+        }
+
+        let address = h.object("LinkedList`1", None);
+        h.write_u64(address + 0x10, nodes[0]);
+        h.write_u32(address + 0x18, 3);
+
+        let list: LinkedList<u64> = h.get(address);
+        assert_eq!(
+            list.iter().unwrap().collect::<Result<Vec<_>>>().unwrap(),
+            [10, 20, 30]
+        );
+
+        // a broken chain ends the list early
+        h.write_u64(nodes[1] + 0x18, 0);
+        let list: LinkedList<u64> = h.get(address);
+        assert!(list.iter().unwrap().collect::<Result<Vec<_>>>().is_err());
+    }
+}
@@ -0,0 +1,705 @@
+use std::{
+    io::{stdin, stdout, Write},
+    ops::Range,
+    sync::Arc,
+};
+
+use anyhow::{bail, Context};
+
+use btd6_tool_dump::full_name;
+
+use crate::{
+    btd::{types::CSharpString, BloonsGame},
+    memory::{
+        reflection::{FieldInfo, TypeKind, TypeRef},
+        ClassRegistry, Object, ObjectPointer, Pointer, ProcessMemoryView, TypeInfo,
+    },
+    offsets, Result,
+};
+
+/// how many elements `ls` shows when no count is given
+const DEFAULT_COUNT: usize = 32;
+const DEFAULT_DUMP_LEN: u64 = 0x100;
+/// the most `x` reads at once, so a mistyped length can't allocate gigabytes
+const MAX_DUMP_LEN: u64 = 0x10000;
+
+const HELP: &str = "\
+ls [start] [count]   list the current object's fields, or an array's elements
+cd <path>            follow fields and indices, e.g. cd unityToSimulation.simulation.towers[0]
+cd ..                go back to the previous object
+cd /                 go back to InGame
+goto <address>       inspect the object at an address
+x [address] [len]    hex dump of up to 0x10000 bytes, from the current object by default, in hex
+q                    quit";
+
+/// An object, or a value type stored inside one, being inspected
+#[derive(Debug, Clone)]
+struct Node {
+    name: String,
+    address: u64,
+    class: TypeInfo,
+    /// whether `address` is an object with a header, rather than a value type's fields
+    boxed: bool,
+}
+
+impl Node {
+    /// the address of a field, whose offset counts the object header even in unboxed value types
+    fn field_address(&self, field: &FieldInfo) -> Result<u64> {
+        let offset = field.get_offset()? as u64;
+
+        match self.boxed {
+            true => Ok(self.address + offset),
+            false => Ok(self.address + offset - 0x10),
+        }
+    }
+}
+
+/// A REPL for browsing the game's objects from InGame, by following fields named in il2cpp's metadata
+pub struct Inspector {
+    game: BloonsGame,
+    memory: ProcessMemoryView,
+    registry: Arc<ClassRegistry>,
+    path: Vec<Node>,
+}
+
+impl Inspector {
+    pub fn new(game: BloonsGame) -> Result<Self> {
+        let registry = game.registry().context("class registry not found")?.clone();
+        let memory = game.memory().clone();
+
+        Ok(Self {
+            game,
+            memory,
+            registry,
+            path: vec![],
+        })
+    }
+
+    pub fn game(&self) -> &BloonsGame {
+        &self.game
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        println!("{}", HELP);
+        self.reset()?;
+
+        loop {
+            print!("{}> ", self.get_path());
+            stdout().flush()?;
+
+            let mut line = String::new();
+            if stdin().read_line(&mut line)? == 0 {
+                break;
+            }
+
+            // values are re-read for every command
+            self.game.invalidate();
+
+            let mut words = line.split_whitespace();
+            let result = match words.next() {
+                None | Some("ls") => self.list(words.next(), words.next()),
+                Some("cd") => self.change(words.next().unwrap_or("/")),
+                Some("goto") => match words.next() {
+                    Some(address) => self.goto(address),
+                    None => Err(anyhow::anyhow!("usage: goto <address>")),
+                },
+                Some("x") => self.dump(words.next(), words.next()),
+                Some("help") => {
+                    println!("{}", HELP);
+                    Ok(())
+                }
+                Some("q") | Some("quit") => break,
+                Some(command) => Err(anyhow::anyhow!("unknown command: {}", command)),
+            };
+
+            if let Err(e) = result {
+                println!("error: {:#}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_path(&self) -> String {
+        let names = self.path.iter().skip(1).map(|n| n.name.as_str());
+
+        std::iter::once("InGame")
+            .chain(names)
+            .collect::<Vec<_>>()
+            .join(".")
+            .replace(".[", "[")
+    }
+
+    fn current(&self) -> &Node {
+        self.path.last().unwrap()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.path = vec![self.get_ingame()?];
+
+        Ok(())
+    }
+
+    fn get_ingame(&self) -> Result<Node> {
+        let ingame = self.game.get_ingame()?.context("not in game")?;
+
+        self.get_object("InGame".to_string(), ingame.0.address)
+    }
+
+    /// resolves a path from InGame, or from the simulation if it starts with `simulation`, and
+    /// describes the value at its end
+    pub fn read_path(&self, path: &str) -> Result<String> {
+        let mut segments = parse_path(path)?;
+        let last = segments.pop().context("empty path")?;
+
+        let mut node = match segments.first() {
+            Some(Segment::Field(name)) if name == "simulation" => {
+                segments.remove(0);
+
+                let ingame = self.game.get_ingame()?.context("not in game")?;
+                let simulation = ingame.unity_to_simulation()?.simulation()?;
+                self.get_object("simulation".to_string(), simulation.0.address)?
+            }
+
+            _ => self.get_ingame()?,
+        };
+
+        for segment in segments {
+            node = match segment {
+                Segment::Field(name) => self.get_field(&node, &name)?,
+                Segment::Index(index) => self.get_element(&node, index)?,
+            };
+        }
+
+        let (address, value_type) = match last {
+            Segment::Field(name) => {
+                let (field, address) = self.find_field(&node, &name)?;
+                (address, field.get_type()?)
+            }
+
+            Segment::Index(index) => self.find_element(&node, index)?,
+        };
+
+        self.preview(address, &value_type)
+    }
+
+    fn change(&mut self, path: &str) -> Result<()> {
+        match path {
+            "/" => return self.reset(),
+
+            ".." => {
+                if self.path.len() > 1 {
+                    self.path.pop();
+                }
+                return Ok(());
+            }
+
+            _ => {}
+        }
+
+        // only move once the whole path resolves
+        let mut nodes = vec![];
+        let mut node = self.current().clone();
+
+        for segment in parse_path(path)? {
+            node = match segment {
+                Segment::Field(name) => self.get_field(&node, &name)?,
+                Segment::Index(index) => self.get_element(&node, index)?,
+            };
+
+            nodes.push(node.clone());
+        }
+
+        self.path.extend(nodes);
+
+        Ok(())
+    }
+
+    fn goto(&mut self, address: &str) -> Result<()> {
+        let address = parse_address(address)?;
+        let node = self.get_object(format!("{{{:#x}}}", address), address)?;
+
+        self.path.push(node);
+
+        Ok(())
+    }
+
+    fn list(&self, start: Option<&str>, count: Option<&str>) -> Result<()> {
+        let start = start.map(str::parse).transpose()?.unwrap_or(0);
+        let count = count.map(str::parse).transpose()?.unwrap_or(DEFAULT_COUNT);
+
+        let node = self.current();
+        let type_name = self.get_type_name(&node.class)?;
+        println!("{} {:#x} {}", node.name, node.address, type_name);
+
+        if node.boxed && is_array(&node.class)? {
+            return self.list_elements(node, start, count);
+        }
+
+        if node.boxed && type_name == "string" {
+            let value = CSharpString::try_from(self.pointer(node.address))?;
+            println!("  {:?}", value.read_string()?);
+            return Ok(());
+        }
+
+        for field in node.class.get_instance_fields()? {
+            self.print_field("", &field, node.field_address(&field)?)?;
+        }
+
+        let statics = node.class.get_statics().ok();
+
+        for field in node.class.get_fields()? {
+            // literals have no storage and thread statics an offset of -1
+            if !field.is_static()? || field.is_literal()? || field.get_offset()? < 0 {
+                continue;
+            }
+
+            if let Some(statics) = &statics {
+                let address = statics.0.address + field.get_offset()? as u64;
+                self.print_field("static ", &field, address)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn list_elements(&self, node: &Node, start: usize, count: usize) -> Result<()> {
+        let len = self.get_array_len(node)?;
+        println!("  length {}", len);
+
+        let element_type = get_element_type(&node.class)?;
+
+        for index in element_range(len, start, count) {
+            let address = self.get_element_address(node, index)?;
+            println!("  [{}] {}", index, self.preview(address, &element_type)?);
+        }
+
+        Ok(())
+    }
+
+    fn print_field(&self, prefix: &str, field: &FieldInfo, address: u64) -> Result<()> {
+        let field_type = field.get_type()?;
+
+        println!(
+            "  {}{:#06x} {:<32} {} = {}",
+            prefix,
+            field.get_offset()?,
+            field_type.get_name(&self.registry)?,
+            field.get_name()?,
+            self.preview(address, &field_type)
+                .unwrap_or_else(|e| format!("<{}>", e))
+        );
+
+        Ok(())
+    }
+
+    /// a short description of the value stored at an address
+    fn preview(&self, address: u64, value_type: &TypeRef) -> Result<String> {
+        let memory = &self.memory;
+
+        let value = match value_type.get_kind()? {
+            TypeKind::Boolean => memory.read::<bool>(address)?.to_string(),
+            TypeKind::I1 => (self.read_bytes::<1>(address)?[0] as i8).to_string(),
+            TypeKind::U1 => self.read_bytes::<1>(address)?[0].to_string(),
+            TypeKind::I2 => i16::from_le_bytes(self.read_bytes(address)?).to_string(),
+            TypeKind::U2 => u16::from_le_bytes(self.read_bytes(address)?).to_string(),
+            TypeKind::Char => {
+                let value = u16::from_le_bytes(self.read_bytes(address)?);
+                format!("{:?}", char::from_u32(value as u32).unwrap_or('?'))
+            }
+            TypeKind::I4 => memory.read::<i32>(address)?.to_string(),
+            TypeKind::U4 => memory.read::<u32>(address)?.to_string(),
+            TypeKind::I8 => memory.read::<i64>(address)?.to_string(),
+            TypeKind::U8 => memory.read::<u64>(address)?.to_string(),
+            TypeKind::I | TypeKind::U | TypeKind::Ptr => {
+                format!("{:#x}", memory.read::<u64>(address)?)
+            }
+            TypeKind::R4 => memory.read::<f32>(address)?.to_string(),
+            TypeKind::R8 => memory.read::<f64>(address)?.to_string(),
+
+            TypeKind::String => match memory.read::<Option<CSharpString>>(address)? {
+                Some(value) => format!("{:?}", value.read_string()?),
+                None => "null".to_string(),
+            },
+
+            TypeKind::ValueType => format!("{{{}}}", value_type.get_name(&self.registry)?),
+            TypeKind::GenericInst if value_type.is_value_type()? => {
+                format!("{{{}}}", value_type.get_name(&self.registry)?)
+            }
+
+            _ => {
+                let pointer: Pointer = memory.read(address)?;
+                if pointer.address == 0 {
+                    return Ok("null".to_string());
+                }
+
+                let node = self.get_object(String::new(), pointer.address)?;
+                let type_name = self.get_type_name(&node.class)?;
+
+                match is_array(&node.class)? {
+                    true => format!(
+                        "{:#x} {} (length {})",
+                        node.address,
+                        type_name,
+                        self.get_array_len(&node)?
+                    ),
+                    false => format!("{:#x} {}", node.address, type_name),
+                }
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn get_field(&self, node: &Node, name: &str) -> Result<Node> {
+        let (field, address) = self.find_field(node, name)?;
+
+        self.follow(name.to_string(), address, &field.get_type()?)
+    }
+
+    /// a field of the node's class or its base types, and the address of its value, by its C# name
+    /// or the name of the accessor it is bound to
+    fn find_field(&self, node: &Node, name: &str) -> Result<(FieldInfo, u64)> {
+        let backing_field = format!("<{}>k__BackingField", name);
+
+        let mut class = Some(node.class.clone());
+        while let Some(current) = class {
+            let class_name = full_name(&current.get_namespace()?, &current.get_full_name()?);
+            let bound_field = offsets::find_bound_field(&class_name, name);
+
+            for field in current.get_fields()? {
+                let field_name = field.get_name()?;
+                if field_name != name
+                    && field_name != backing_field
+                    && Some(field_name.as_str()) != bound_field
+                {
+                    continue;
+                }
+
+                // like `ls`, skips thread statics, which have no slot in the statics, and constants
+                let offset = field.get_offset()?;
+                if offset < 0 || field.is_literal()? {
+                    bail!(
+                        "{} is constant or thread static, so it has no address",
+                        field_name
+                    );
+                }
+
+                let address = match field.is_static()? {
+                    true => current.get_statics()?.0.address + offset as u64,
+                    false => node.field_address(&field)?,
+                };
+
+                return Ok((field, address));
+            }
+
+            class = current.get_base_type()?;
+        }
+
+        bail!("no field named {}", name)
+    }
+
+    fn get_element(&self, node: &Node, index: usize) -> Result<Node> {
+        let (address, element_type) = self.find_element(node, index)?;
+
+        self.follow(format!("[{}]", index), address, &element_type)
+    }
+
+    /// an element of an array or list, and the address of its value
+    fn find_element(&self, node: &Node, index: usize) -> Result<(u64, TypeRef)> {
+        // lists are indexed through their backing array, up to their size
+        if node.boxed && node.class.get_name()? == "List`1" {
+            let (_, size) = self.find_field(node, "_size")?;
+            let items = self.get_field(node, "_items")?;
+
+            let size: i32 = self.memory.read(size)?;
+            if index >= size as usize {
+                bail!("index {} out of range for list of {}", index, size);
+            }
+
+            return self.find_element(&items, index);
+        }
+
+        if !node.boxed || !is_array(&node.class)? {
+            bail!("{} is not an array or list", node.name);
+        }
+
+        let len = self.get_array_len(node)?;
+        if index >= len {
+            bail!("index {} out of range for array of {}", index, len);
+        }
+
+        let address = self.get_element_address(node, index)?;
+        Ok((address, get_element_type(&node.class)?))
+    }
+
+    /// the object or value type stored at an address
+    fn follow(&self, name: String, address: u64, value_type: &TypeRef) -> Result<Node> {
+        let kind = value_type.get_kind()?;
+
+        let is_value = match kind {
+            TypeKind::ValueType => true,
+            TypeKind::GenericInst => value_type.is_value_type()?,
+            TypeKind::String | TypeKind::Object => false,
+            TypeKind::Class | TypeKind::SzArray | TypeKind::Array => false,
+            _ => bail!("{} is a {}, not an object", name, kind),
+        };
+
+        if is_value {
+            let class = value_type
+                .get_class(&self.registry)?
+                .context(format!("class of {} not found", name))?;
+
+            return Ok(Node {
+                name,
+                address,
+                class,
+                boxed: false,
+            });
+        }
+
+        let pointer: Pointer = self.memory.read(address)?;
+        if pointer.address == 0 {
+            bail!("{} is null", name);
+        }
+
+        self.get_object(name, pointer.address)
+    }
+
+    fn get_object(&self, name: String, address: u64) -> Result<Node> {
+        let object = Object(self.pointer(address));
+
+        Ok(Node {
+            name,
+            address,
+            class: object.get_type()?,
+            boxed: true,
+        })
+    }
+
+    fn get_type_name(&self, class: &TypeInfo) -> Result<String> {
+        class.get_type_ref().get_name(&self.registry)
+    }
+
+    fn get_array_len(&self, node: &Node) -> Result<usize> {
+        Ok(self.memory.read::<u32>(node.address + 0x18)? as usize)
+    }
+
+    fn get_element_address(&self, node: &Node, index: usize) -> Result<u64> {
+        let stride = node.class.get_element_size()? as u64;
+
+        Ok(node.address + 0x20 + stride * index as u64)
+    }
+
+    fn dump(&self, address: Option<&str>, len: Option<&str>) -> Result<()> {
+        let address = match address {
+            Some(address) => parse_address(address)?,
+            None => self.current().address,
+        };
+
+        let len = dump_len(len)?;
+
+        let mut data = vec![0; len as usize];
+        self.memory.read_exact(address, &mut data)?;
+
+        for (i, line) in data.chunks(16).enumerate() {
+            let hex = line
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>();
+            let ascii = line
+                .iter()
+                .map(|&b| match b.is_ascii_graphic() {
+                    true => b as char,
+                    false => '.',
+                })
+                .collect::<String>();
+
+            println!(
+                "{:#014x}  {:<47}  {}",
+                address + 16 * i as u64,
+                hex.join(" "),
+                ascii
+            );
+        }
+
+        Ok(())
+    }
+
+    fn pointer(&self, address: u64) -> Pointer {
+        Pointer {
+            memory: self.memory.clone(),
+            address,
+        }
+    }
+
+    fn read_bytes<const N: usize>(&self, address: u64) -> Result<[u8; N]> {
+        let mut buffer = [0; N];
+        self.memory.read_exact(address, &mut buffer)?;
+
+        Ok(buffer)
+    }
+}
+
+/// the length of a hex dump, clamped to `MAX_DUMP_LEN`
+fn dump_len(len: Option<&str>) -> Result<u64> {
+    let len = len
+        .map(parse_address)
+        .transpose()?
+        .unwrap_or(DEFAULT_DUMP_LEN);
+
+    Ok(len.min(MAX_DUMP_LEN))
+}
+
+/// the indices of up to `count` elements from `start`, stopping at the end of the array
+fn element_range(len: usize, start: usize, count: usize) -> Range<usize> {
+    start..len.min(start.saturating_add(count))
+}
+
+enum Segment {
+    Field(String),
+    Index(usize),
+}
+
+/// splits `a.b[2].c` into fields and indices
+fn parse_path(path: &str) -> Result<Vec<Segment>> {
+    let mut segments = vec![];
+
+    for part in path.split('.') {
+        let (name, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+
+        if !name.is_empty() {
+            segments.push(Segment::Field(name.to_string()));
+        }
+
+        while let Some(index) = rest.strip_prefix('[') {
+            let (index, next) = index.split_once(']').context("missing ]")?;
+
+            segments.push(Segment::Index(index.parse()?));
+            rest = next;
+        }
+
+        if !rest.is_empty() {
+            bail!("invalid path: {}", path);
+        }
+    }
+
+    Ok(segments)
+}
+
+fn parse_address(value: &str) -> Result<u64> {
+    let value = value.trim_start_matches("0x");
+
+    Ok(u64::from_str_radix(value, 16)?)
+}
+
+fn is_array(class: &TypeInfo) -> Result<bool> {
+    Ok(matches!(
+        class.get_type_ref().get_kind()?,
+        TypeKind::SzArray | TypeKind::Array
+    ))
+}
+
+fn get_element_type(class: &TypeInfo) -> Result<TypeRef> {
+    let element_class = class
+        .get_element_class()?
+        .context("array without element class")?;
+
+    Ok(element_class.get_type_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{Scene, FIELD_LITERAL, FIELD_STATIC, KIND_CLASS, KIND_I4};
+
+    /// a scene whose InGame class describes a bound field, a static, a thread static and a constant
+    fn inspector() -> (Scene, Inspector) {
+        let mut scene = Scene::new();
+        let h = &mut scene.heap;
+
+        h.register_classes();
+
+        let bridge = h.class("UnityToSimulation", None) + 0x20;
+        let instance = h.type_ref(KIND_CLASS, 0, FIELD_STATIC);
+        let thread_static = h.type_ref(KIND_I4, 0, FIELD_STATIC);
+        let constant = h.type_ref(KIND_I4, 0, FIELD_STATIC | FIELD_LITERAL);
+        let bridge_offset = 0x10 + offsets::testing::offset("InGame", "bridge");
+
+        h.fields(
+            "InGame",
+            &[
+                ("bridge", bridge, bridge_offset as i32),
+                ("instance", instance, 0),
+                ("towerCount", thread_static, -1),
+                ("MaxTowers", constant, 0),
+            ],
+        );
+
+        let mut inspector = Inspector::new(h.game()).unwrap();
+        inspector.reset().unwrap();
+
+        (scene, inspector)
+    }
+
+    #[test]
+    fn finds_fields_by_name_or_accessor() {
+        let (scene, mut inspector) = inspector();
+        let ingame = inspector.current().clone();
+
+        let bridge = scene.heap.field(ingame.address, "InGame", "bridge");
+        assert_eq!(inspector.find_field(&ingame, "bridge").unwrap().1, bridge);
+        assert_eq!(
+            inspector
+                .find_field(&ingame, "unity_to_simulation")
+                .unwrap()
+                .1,
+            bridge
+        );
+
+        let statics = ingame.class.get_statics().unwrap().0.address;
+        assert_eq!(
+            inspector.find_field(&ingame, "instance").unwrap().1,
+            statics
+        );
+
+        inspector.change("bridge").unwrap();
+        assert_eq!(inspector.get_path(), "InGame.bridge");
+        assert_eq!(
+            inspector.current().address,
+            scene.heap.view().read::<u64>(bridge).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_fields_without_an_address() {
+        let (_scene, inspector) = inspector();
+        let ingame = inspector.current().clone();
+
+        for name in ["towerCount", "MaxTowers"] {
+            let error = inspector.find_field(&ingame, name).unwrap_err();
+            assert!(error.to_string().contains("no address"), "{}", error);
+        }
+
+        assert!(inspector.find_field(&ingame, "missing").is_err());
+    }
+
+    #[test]
+    fn clamps_hex_dumps() {
+        assert_eq!(dump_len(None).unwrap(), DEFAULT_DUMP_LEN);
+        assert_eq!(dump_len(Some("0x20")).unwrap(), 0x20);
+        assert_eq!(dump_len(Some("ffffffffffff")).unwrap(), MAX_DUMP_LEN);
+        assert!(dump_len(Some("zz")).is_err());
+
+        let (_scene, inspector) = inspector();
+        inspector.dump(None, Some("ffffffffffff")).unwrap();
+    }
+
+    #[test]
+    fn clamps_listed_elements() {
+        assert_eq!(element_range(100, 0, DEFAULT_COUNT), 0..32);
+        assert_eq!(element_range(100, 90, DEFAULT_COUNT), 90..100);
+        assert_eq!(element_range(100, 10, usize::MAX), 10..100);
+        assert!(element_range(100, 200, 5).is_empty());
+    }
+}
@@ -1,13 +1,23 @@
 mod app;
 mod btd;
+mod inspect;
 mod memory;
+mod offsets;
 mod process;
+#[cfg(test)]
+mod testing;
+mod watch;
+#[cfg(windows)]
 mod win32_util;
 
-use anyhow::bail;
+use std::{fs, time::Instant};
+
+use anyhow::{bail, Context};
 use app::App;
-use btd::BloonsGame;
-use process::Process;
+use btd::{BloonModelCache, BloonsGame};
+use btd6_tool_dump::{ClassLayouts, Dump, Script};
+use btd6_tool_metadata::{pe::PeFile, Layouts, Metadata, SANITY};
+use offsets::{BuildOffsets, OffsetsFile};
 
 use crate::memory::ObjectPointer;
 
@@ -37,65 +47,236 @@ impl<T: PartialEq> Previous<T> {
 }
 
 fn main() -> Result<()> {
-    if std::env::args().nth(1).is_some_and(|v| v == "test") {
-        let mut game = BloonsGame::find_game()?;
+    let args = std::env::args().collect::<Vec<_>>();
 
-        let Some(ingame) = game.get_ingame()? else {
-            bail!("not in game");
-        };
+    offsets::init(offsets::default_path()?)?;
+
+    match args.get(1).map(String::as_str) {
+        Some("test") => test(args.get(2)),
+
+        Some("rounds") => rounds(args.get(2)),
+
+        Some("bench") => bench(args.get(2)),
+
+        Some("snapshot") => {
+            let path = args.get(2).context("usage: btd6-tool snapshot <file>")?;
+            snapshot(path)
+        }
+
+        Some("offsets") => {
+            let usage = "usage: btd6-tool offsets <GameAssembly.dll> <dump.cs | global-metadata.dat> [script.json]";
+            let [assembly, layouts] = [2, 3].map(|i| args.get(i).context(usage));
+            offsets(assembly?, layouts?, args.get(4))
+        }
 
-        let type_info = ingame.get_type()?;
+        Some("inspect") => {
+            let mut inspector = inspect::Inspector::new(open_game(args.get(2))?)?;
+            inspector.run()
+        }
 
-        let mut node = type_info;
+        Some("watch") => {
+            let path = args
+                .get(2)
+                .context("usage: btd6-tool watch <path> [out.csv]")?;
 
-        println!("{}", node.get_name()?);
-        while let Some(base) = node.get_base_type()? {
-            println!("{}", base.get_name()?);
-            node = base;
+            let mut watcher = watch::Watcher::new(
+                BloonsGame::find_game()?,
+                path,
+                args.get(3).map(String::as_str),
+            )?;
+            watcher.run()
         }
 
-        game.try_get_summary()?;
+        Some("replay") => {
+            let path = args.get(2).context("usage: btd6-tool replay <file>")?;
+            replay(path)
+        }
 
-        // let simulation = ingame.unity_to_simulation()?;
+        _ => {
+            let mut app = App::new();
+            app.run()
+        }
+    }
+}
 
-        // for tower in simulation.towers()?.iter()? {
-        //     let tower = tower?;
-        //     for ability in tower.abilities()?.iter()? {
-        //         let ability = ability?;
+/// captures every page read while building a summary so the read path can be reproduced offline
+fn snapshot(path: &str) -> Result<()> {
+    let game = BloonsGame::find_game()?;
+    let (mut recording, recorder) = game.record()?;
 
-        //         println!("{} {}", ability.ability()?.model()?.name()?, ability.ability()?.cooldown_remaining()?);
-        //     }
-        // }
+    let result = recording.try_get_summary();
 
-        // println!(
-        //     "{} {} {}",
-        //     simulation.map()?.spawner()?.current_round()?.get()?,
-        //     simulation.time()?.elapsed()?,
-        //     simulation.round_time()?.elapsed()?
-        // );
+    recorder.snapshot().save(path)?;
+    println!("saved {} pages to {}", recorder.page_count(), path);
 
-        // let model = simulation.model()?;
-        // let cache = BloonModelCache::load(&model)?;
+    match result {
+        Ok(summary) => println!("{:#?}", summary),
+        Err(e) => println!("summary failed: {:?}", e),
+    }
+
+    Ok(())
+}
 
-        // for (i, round) in model.round_set()?.rounds()?.iter()?.enumerate() {
-        //     let round = round?;
+/// adds the offsets for a game build to offsets.json, from either Il2CppDumper's output or the
+/// game's own global-metadata.dat
+fn offsets(assembly: &str, layouts: &str, script: Option<&String>) -> Result<()> {
+    let assembly = fs::read(assembly)?;
+    let pe = PeFile::parse(&assembly)?;
+    let build_id = offsets::build_id(pe.headers.timestamp);
 
-        //     let mut worth = 0.0;
+    let data = fs::read(layouts)?;
+    let layouts: Box<dyn ClassLayouts> = match data.starts_with(&SANITY.to_le_bytes()) {
+        true => Box::new(Layouts::read(&pe, Metadata::parse(&data)?)?),
 
-        //     for group in round.groups()?.iter()? {
-        //         let group = group?;
+        false => Box::new(Dump::parse(&String::from_utf8(data)?)?),
+    };
 
-        //         let bloon = cache.get(group.bloon()?.to_string()).unwrap();
+    let script = match script {
+        Some(path) => Some(Script::parse(&fs::read_to_string(path)?)?),
+        None => None,
+    };
 
-        //         worth += group.count()? as f32 * bloon.worth(i as u64 + 1);
-        //     }
+    let (build, missing) = BuildOffsets::generate(layouts.as_ref(), script.as_ref());
 
-        //     println!("{: >3}: {: >6.1}", i + 1, worth);
-        // }
-    } else {
-        let mut app = App::new();
-        app.run()?;
+    let path = offsets::default_path()?;
+    let mut file = match path.exists() {
+        true => OffsetsFile::load(&path)?,
+        false => OffsetsFile::default(),
+    };
+
+    println!(
+        "build {}: {} classes, {} type infos, saved to {}",
+        build_id,
+        build.fields.len(),
+        build.type_info.len(),
+        path.display()
+    );
+
+    if !missing.is_empty() {
+        println!(
+            "{} bound fields are missing from this build:",
+            missing.len()
+        );
+
+        for field in &missing {
+            println!("  {}", field);
+        }
     }
 
+    file.builds.insert(build_id, build);
+    file.save(&path)?;
+
+    Ok(())
+}
+
+/// attaches to the running game, or loads a snapshot or minidump if a path is given
+fn open_game(path: Option<&String>) -> Result<BloonsGame> {
+    match path {
+        Some(path) => BloonsGame::load_file(path),
+        None => BloonsGame::find_game(),
+    }
+}
+
+fn replay(path: &str) -> Result<()> {
+    let mut game = BloonsGame::load_file(path)?;
+
+    println!("{:#?}", game.try_get_summary()?);
+
+    Ok(())
+}
+
+/// compares reading summaries with and without the page cache
+fn bench(path: Option<&String>) -> Result<()> {
+    const ITERATIONS: u32 = 20;
+
+    let game = open_game(path)?;
+
+    for (label, mut game) in [("uncached", game.without_cache()), ("cached", game)] {
+        let start = Instant::now();
+
+        for _ in 0..ITERATIONS {
+            game.invalidate();
+            game.try_get_summary()?;
+        }
+
+        println!(
+            "{}: {:?} per summary, {}",
+            label,
+            start.elapsed() / ITERATIONS,
+            game.memory_stats()
+        );
+    }
+
+    Ok(())
+}
+
+fn rounds(path: Option<&String>) -> Result<()> {
+    let game = open_game(path)?;
+
+    let Some(ingame) = game.get_ingame()? else {
+        bail!("not in game");
+    };
+
+    let model = ingame.unity_to_simulation()?.simulation()?.model()?;
+    let cache = BloonModelCache::load(&model)?;
+
+    for (i, round) in model.round_set()?.rounds()?.iter()?.enumerate() {
+        let round = round?;
+
+        let mut worth = 0.0;
+
+        for group in round.groups()?.iter()? {
+            let group = group?;
+
+            let bloon = cache
+                .get(group.bloon()?.read_string()?)
+                .context("unknown bloon")?;
+
+            worth += group.count()? as f32 * bloon.worth(i as u64 + 1);
+        }
+
+        println!("{: >3}: {: >6.1}", i + 1, worth);
+    }
+
+    Ok(())
+}
+
+fn test(path: Option<&String>) -> Result<()> {
+    let mut game = open_game(path)?;
+
+    let Some(ingame) = game.get_ingame()? else {
+        bail!("not in game");
+    };
+
+    let type_info = ingame.get_type()?;
+
+    let mut node = type_info;
+
+    println!("{}", node.get_name()?);
+    while let Some(base) = node.get_base_type()? {
+        println!("{}", base.get_name()?);
+        node = base;
+    }
+
+    game.try_get_summary()?;
+
+    // let simulation = ingame.unity_to_simulation()?;
+
+    // for tower in simulation.towers()?.iter()? {
+    //     let tower = tower?;
+    //     for ability in tower.abilities()?.iter()? {
+    //         let ability = ability?;
+
+    //         println!("{} {}", ability.ability()?.model()?.name()?, ability.ability()?.cooldown_remaining()?);
+    //     }
+    // }
+
+    // println!(
+    //     "{} {} {}",
+    //     simulation.map()?.spawner()?.current_round()?.get()?,
+    //     simulation.time()?.elapsed()?,
+    //     simulation.round_time()?.elapsed()?
+    // );
+
     Ok(())
 }
@@ -0,0 +1,183 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use super::{MemorySource, PAGE_SIZE};
+use crate::Result;
+
+/// Whole pages read from a memory source, so that nearby field reads share one underlying read
+#[derive(Debug, Default)]
+pub struct PageCache {
+    pages: Mutex<HashMap<u64, Arc<[u8]>>>,
+}
+
+impl PageCache {
+    pub fn clear(&self) {
+        self.pages.lock().unwrap().clear();
+    }
+
+    pub fn read(
+        &self,
+        source: &dyn MemorySource,
+        stats: &MemoryStats,
+        address: u64,
+        buffer: &mut [u8],
+    ) -> Result<usize> {
+        let page_address = address & !(PAGE_SIZE - 1);
+
+        let cached = self.pages.lock().unwrap().get(&page_address).cloned();
+
+        let page = match cached {
+            Some(page) => {
+                stats.hits.fetch_add(1, Ordering::Relaxed);
+                page
+            }
+
+            None => {
+                stats.misses.fetch_add(1, Ordering::Relaxed);
+
+                let mut page = vec![0; PAGE_SIZE as usize];
+                match stats.read_source(source, page_address, &mut page) {
+                    Ok(count) if count == page.len() => {}
+
+                    // partially readable page, don't cache it
+                    _ => return stats.read_source(source, address, buffer),
+                }
+
+                let page: Arc<[u8]> = page.into();
+                self.pages
+                    .lock()
+                    .unwrap()
+                    .insert(page_address, page.clone());
+                page
+            }
+        };
+
+        let offset = (address - page_address) as usize;
+        let count = buffer.len().min(page.len() - offset);
+        buffer[..count].copy_from_slice(&page[offset..offset + count]);
+
+        Ok(count)
+    }
+}
+
+/// Counters for reads made through a `ProcessMemoryView`
+#[derive(Debug, Default)]
+pub struct MemoryStats {
+    reads: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    source_reads: AtomicU64,
+    source_nanos: AtomicU64,
+    /// collections read with `read_versioned`, and how many of those the game modified mid-read
+    versioned_reads: AtomicU64,
+    torn_reads: AtomicU64,
+}
+
+impl MemoryStats {
+    pub fn record_read(&self) {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_versioned_read(&self, torn: bool) {
+        self.versioned_reads.fetch_add(1, Ordering::Relaxed);
+        if torn {
+            self.torn_reads.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn read_source(
+        &self,
+        source: &dyn MemorySource,
+        address: u64,
+        buffer: &mut [u8],
+    ) -> Result<usize> {
+        let start = Instant::now();
+        let result = source.read_memory(address, buffer);
+
+        self.source_reads.fetch_add(1, Ordering::Relaxed);
+        self.source_nanos
+            .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+
+        result
+    }
+
+    pub fn reset(&self) {
+        for counter in [
+            &self.reads,
+            &self.hits,
+            &self.misses,
+            &self.source_reads,
+            &self.source_nanos,
+            &self.versioned_reads,
+            &self.torn_reads,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    pub fn report(&self) -> MemoryStatsReport {
+        MemoryStatsReport {
+            reads: self.reads.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            source_reads: self.source_reads.load(Ordering::Relaxed),
+            source_time: Duration::from_nanos(self.source_nanos.load(Ordering::Relaxed)),
+            versioned_reads: self.versioned_reads.load(Ordering::Relaxed),
+            torn_reads: self.torn_reads.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStatsReport {
+    pub reads: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub source_reads: u64,
+    pub source_time: Duration,
+    pub versioned_reads: u64,
+    pub torn_reads: u64,
+}
+
+impl MemoryStatsReport {
+    /// the percentage of versioned reads that saw the game change mid-read
+    pub fn torn_rate(&self) -> f64 {
+        match self.versioned_reads {
+            0 => 0.0,
+            total => self.torn_reads as f64 / total as f64 * 100.0,
+        }
+    }
+}
+
+impl Display for MemoryStatsReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hit_rate = match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64 * 100.0,
+        };
+
+        let average = match self.source_reads {
+            0 => Duration::ZERO,
+            count => self.source_time / count as u32,
+        };
+
+        write!(
+            f,
+            "{} reads, {} source reads in {:?} (avg {:?}), {:.1}% cache hits, {:.1}% of {} collection reads torn",
+            self.reads,
+            self.source_reads,
+            self.source_time,
+            average,
+            hit_rate,
+            self.torn_rate(),
+            self.versioned_reads,
+        )
+    }
+}
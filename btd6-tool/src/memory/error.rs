@@ -0,0 +1,72 @@
+use std::fmt::Display;
+
+/// Why a read of the game's memory failed, raised through `crate::Result` and recovered with
+/// `MemoryError::find`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemoryError {
+    /// the address is not mapped, or could not be read
+    Unreadable { address: u64 },
+    /// a pointer that should refer to an object was null, read from `address`, or 0 if it wasn't
+    /// read from memory
+    Null { address: u64, expected: String },
+    /// an object is not of the class it was read as
+    TypeMismatch {
+        address: u64,
+        expected: String,
+        actual: String,
+    },
+    /// the game modified a collection while it was being read, even after retrying
+    Torn { address: u64 },
+    /// the process being read has exited
+    ProcessGone,
+}
+
+impl MemoryError {
+    /// the memory error an error was caused by, if any
+    pub fn find(error: &anyhow::Error) -> Option<&MemoryError> {
+        error.downcast_ref()
+    }
+}
+
+impl Display for MemoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemoryError::Unreadable { address } => write!(f, "cannot read {:#x}", address),
+            MemoryError::Null {
+                address: 0,
+                expected,
+            } => write!(f, "Expected {} got null", expected),
+            MemoryError::Null { address, expected } => {
+                write!(f, "Expected {} got null at {:#x}", expected, address)
+            }
+            MemoryError::TypeMismatch {
+                expected, actual, ..
+            } => write!(f, "Expected {} got {}", expected, actual),
+            MemoryError::Torn { address } => {
+                write!(f, "collection at {:#x} changed while being read", address)
+            }
+            MemoryError::ProcessGone => write!(f, "the game process has exited"),
+        }
+    }
+}
+
+impl std::error::Error for MemoryError {}
+
+/// The bound field being read when an error occurred, added as context by field accessors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldContext {
+    pub class: &'static str,
+    pub field: &'static str,
+    /// the object the field was read from
+    pub address: u64,
+}
+
+impl Display for FieldContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "reading {}.{} of {:#x}",
+            self.class, self.field, self.address
+        )
+    }
+}
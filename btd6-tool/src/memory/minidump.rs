@@ -0,0 +1,202 @@
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+    sync::Mutex,
+};
+
+use anyhow::bail;
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use super::MemorySource;
+use crate::Result;
+
+pub const MAGIC: &[u8; 4] = b"MDMP";
+
+const MODULE_LIST_STREAM: u32 = 4;
+const MEMORY_LIST_STREAM: u32 = 5;
+const MEMORY64_LIST_STREAM: u32 = 9;
+
+/// size of MINIDUMP_MODULE
+const MODULE_SIZE: u64 = 108;
+
+#[derive(Debug, Clone)]
+pub struct MinidumpModule {
+    pub name: String,
+    pub base: u64,
+}
+
+impl MinidumpModule {
+    pub fn base_name(&self) -> &str {
+        self.name.rsplit(['\\', '/']).next().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MemoryRange {
+    address: u64,
+    size: u64,
+    file_offset: u64,
+}
+
+/// A Windows minidump (e.g. from Task Manager's "Create dump file"), read lazily from disk
+#[derive(Debug)]
+pub struct Minidump {
+    file: Mutex<File>,
+    modules: Vec<MinidumpModule>,
+    ranges: Vec<MemoryRange>,
+}
+
+impl Minidump {
+    pub fn load(path: impl AsRef<Path>) -> Result<Minidump> {
+        let mut file = File::open(path)?;
+
+        let mut magic = [0; 4];
+        file.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            bail!("not a minidump file");
+        }
+
+        let _version = file.read_u32::<LittleEndian>()?;
+        let stream_count = file.read_u32::<LittleEndian>()?;
+        let directory_rva = file.read_u32::<LittleEndian>()?;
+
+        let mut streams = vec![];
+        file.seek(SeekFrom::Start(directory_rva as u64))?;
+        for _ in 0..stream_count {
+            let stream_type = file.read_u32::<LittleEndian>()?;
+            let _data_size = file.read_u32::<LittleEndian>()?;
+            let rva = file.read_u32::<LittleEndian>()?;
+
+            streams.push((stream_type, rva as u64));
+        }
+
+        let mut modules = vec![];
+        let mut ranges = vec![];
+
+        for (stream_type, rva) in streams {
+            match stream_type {
+                MODULE_LIST_STREAM => modules = read_module_list(&mut file, rva)?,
+                MEMORY_LIST_STREAM => ranges.extend(read_memory_list(&mut file, rva)?),
+                MEMORY64_LIST_STREAM => ranges.extend(read_memory64_list(&mut file, rva)?),
+                _ => {}
+            }
+        }
+
+        ranges.sort_by_key(|r| r.address);
+
+        Ok(Minidump {
+            file: Mutex::new(file),
+            modules,
+            ranges,
+        })
+    }
+
+    pub fn find_module(&self, base_name: &str) -> Option<&MinidumpModule> {
+        self.modules
+            .iter()
+            .find(|m| m.base_name().eq_ignore_ascii_case(base_name))
+    }
+}
+
+impl MemorySource for Minidump {
+    fn read_memory(&self, address: u64, buffer: &mut [u8]) -> Result<usize> {
+        let index = self.ranges.partition_point(|r| r.address <= address);
+
+        let Some(range) = index.checked_sub(1).map(|i| self.ranges[i]) else {
+            bail!("address not in dump: {:#x}", address);
+        };
+
+        let offset = address - range.address;
+        if offset >= range.size {
+            bail!("address not in dump: {:#x}", address);
+        }
+
+        let count = (buffer.len() as u64).min(range.size - offset) as usize;
+
+        let mut file = self.file.lock().unwrap();
+        file.seek(SeekFrom::Start(range.file_offset + offset))?;
+        file.read_exact(&mut buffer[..count])?;
+
+        Ok(count)
+    }
+}
+
+fn read_module_list(file: &mut File, rva: u64) -> Result<Vec<MinidumpModule>> {
+    file.seek(SeekFrom::Start(rva))?;
+    let count = file.read_u32::<LittleEndian>()?;
+
+    let mut modules = vec![];
+
+    for i in 0..count as u64 {
+        file.seek(SeekFrom::Start(rva + 4 + i * MODULE_SIZE))?;
+
+        let base = file.read_u64::<LittleEndian>()?;
+        let _size = file.read_u32::<LittleEndian>()?;
+        let _checksum = file.read_u32::<LittleEndian>()?;
+        let _timestamp = file.read_u32::<LittleEndian>()?;
+        let name_rva = file.read_u32::<LittleEndian>()?;
+
+        let name = read_string(file, name_rva as u64)?;
+
+        modules.push(MinidumpModule { name, base });
+    }
+
+    Ok(modules)
+}
+
+/// reads a MINIDUMP_STRING, a byte length followed by UTF-16 data
+fn read_string(file: &mut File, rva: u64) -> Result<String> {
+    file.seek(SeekFrom::Start(rva))?;
+    let len = file.read_u32::<LittleEndian>()?;
+
+    let mut data = vec![0; len as usize / 2];
+    file.read_u16_into::<LittleEndian>(&mut data)?;
+
+    Ok(String::from_utf16_lossy(&data))
+}
+
+fn read_memory_list(file: &mut File, rva: u64) -> Result<Vec<MemoryRange>> {
+    file.seek(SeekFrom::Start(rva))?;
+    let count = file.read_u32::<LittleEndian>()?;
+
+    let mut ranges = vec![];
+
+    for _ in 0..count {
+        let address = file.read_u64::<LittleEndian>()?;
+        let size = file.read_u32::<LittleEndian>()? as u64;
+        let file_offset = file.read_u32::<LittleEndian>()? as u64;
+
+        ranges.push(MemoryRange {
+            address,
+            size,
+            file_offset,
+        });
+    }
+
+    Ok(ranges)
+}
+
+/// full-memory dumps store every range back to back starting at a single base rva
+fn read_memory64_list(file: &mut File, rva: u64) -> Result<Vec<MemoryRange>> {
+    file.seek(SeekFrom::Start(rva))?;
+    let count = file.read_u64::<LittleEndian>()?;
+    let mut file_offset = file.read_u64::<LittleEndian>()?;
+
+    let mut ranges = vec![];
+
+    for _ in 0..count {
+        let address = file.read_u64::<LittleEndian>()?;
+        let size = file.read_u64::<LittleEndian>()?;
+
+        ranges.push(MemoryRange {
+            address,
+            size,
+            file_offset,
+        });
+
+        file_offset += size;
+    }
+
+    Ok(ranges)
+}
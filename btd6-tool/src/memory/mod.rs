@@ -0,0 +1,942 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::bail;
+use byteorder::{ByteOrder, NativeEndian};
+
+use crate::Result;
+
+mod cache;
+mod error;
+pub mod minidump;
+pub mod reflection;
+mod regions;
+mod registry;
+pub mod scan;
+pub mod snapshot;
+
+pub use btd6_tool_bindgen::MemoryRead;
+pub use cache::MemoryStatsReport;
+pub use error::{FieldContext, MemoryError};
+pub use regions::RegionMemory;
+pub use registry::ClassRegistry;
+
+use cache::{MemoryStats, PageCache};
+use reflection::{TypeKind, TypeRef};
+
+pub const PAGE_SIZE: u64 = 0x1000;
+
+/// how many times `read_versioned` re-reads a collection the game modified mid-read
+const MAX_TORN_RETRIES: usize = 2;
+
+macro_rules! pointer_type {
+    ($ty:ident) => {
+        #[derive(Debug, Clone)]
+        pub struct $ty(pub Pointer);
+
+        impl crate::memory::MemoryRead for $ty {
+            const SIZE: usize = 8;
+            const ALIGN: usize = 8;
+
+            fn read(view: &ProcessMemoryView, address: u64) -> crate::Result<Self> {
+                match view.read::<Option<$ty>>(address)? {
+                    Some(v) => Ok(v),
+                    None => anyhow::bail!(crate::memory::MemoryError::Null {
+                        address,
+                        expected: stringify!($ty).to_string(),
+                    }),
+                }
+            }
+
+            fn rebind(self, memory: &ProcessMemoryView) -> Self {
+                Self(self.0.rebind(memory))
+            }
+        }
+
+        impl crate::memory::MemoryRead for Option<$ty> {
+            const SIZE: usize = 8;
+            const ALIGN: usize = 8;
+
+            fn read(view: &ProcessMemoryView, address: u64) -> crate::Result<Self> {
+                let value: Pointer = view.read(address)?;
+                if value.address == 0 {
+                    Ok(None)
+                } else {
+                    Ok(Some($ty(value)))
+                }
+            }
+
+            fn rebind(self, memory: &ProcessMemoryView) -> Self {
+                self.map(|value| value.rebind(memory))
+            }
+        }
+    };
+}
+
+macro_rules! object_type {
+    ($ty:ident) => {
+        object_type!($ty<> ; stringify!($ty));
+    };
+
+    ($ty:ident ; $name:expr) => {
+        object_type!($ty<> ; $name);
+    };
+
+    ($ty:ident ; $name:expr ; immutable) => {
+        object_type!(@impl $ty<> ; $name ; true);
+    };
+
+    ($ty:ident<$( $generic:ident ),*>) => {
+        object_type!($ty<$( $generic ),*> ; stringify!($ty));
+    };
+
+    ($ty:ident<$( $generic:ident ),*> ; $name:expr) => {
+        object_type!(@impl $ty<$( $generic ),*> ; $name ; false);
+    };
+
+    (@impl $ty:ident<$( $generic:ident ),*> ; $name:expr ; $immutable:literal) => {
+        #[derive(Debug)]
+        #[allow(unused_parens)]
+        pub struct $ty<$( $generic: MemoryRead ),*>(pub crate::memory::Pointer, std::marker::PhantomData<($( $generic ),*)>);
+
+        impl<$( $generic: MemoryRead ),*> Clone for $ty<$( $generic ),*> {
+            fn clone(&self) -> Self {
+                Self(self.0.clone(), std::default::Default::default())
+            }
+        }
+
+        impl<$( $generic: MemoryRead ),*> crate::memory::MemoryRead for $ty<$( $generic ),*> {
+            const SIZE: usize = crate::memory::Pointer::SIZE;
+            const ALIGN: usize = crate::memory::Pointer::ALIGN;
+
+            fn read(view: &ProcessMemoryView, address: u64) -> crate::Result<Self> {
+                let pointer: crate::memory::Pointer = view.read(address)?;
+                if pointer.address == 0 {
+                    anyhow::bail!(crate::memory::MemoryError::Null {
+                        address,
+                        expected: <Self as crate::memory::MemoryRead>::type_name(),
+                    });
+                }
+
+                $ty::try_from(pointer)
+            }
+
+            fn rebind(self, memory: &ProcessMemoryView) -> Self {
+                // immutable objects stay on the persistent cache, like when they're first read
+                let memory = match $immutable {
+                    true => memory.persistent(),
+                    false => memory.clone(),
+                };
+
+                Self(crate::memory::Pointer { memory, address: self.0.address }, std::default::Default::default())
+            }
+
+            fn type_name() -> String {
+                let arguments: &[String] = &[$( <$generic as crate::memory::MemoryRead>::type_name() ),*];
+
+                match ($name, arguments.is_empty()) {
+                    (_, true) => $name.to_string(),
+                    ("Array", false) => format!("{}[]", arguments[0]),
+                    (_, false) => format!("{}<{}>", $name, arguments.join(", ")),
+                }
+            }
+
+            fn is_type(
+                value_type: &crate::memory::reflection::TypeRef,
+                registry: &crate::memory::ClassRegistry,
+            ) -> crate::Result<bool> {
+                let arguments: &[crate::memory::ArgumentCheck] = &[$( <$generic as crate::memory::MemoryRead>::is_type ),*];
+                let class_name = crate::memory::class_name($name, arguments.len());
+
+                crate::memory::is_type(value_type, registry, &class_name, arguments)
+            }
+        }
+
+        impl<$( $generic: MemoryRead ),*> crate::memory::MemoryRead for Option<$ty<$( $generic ),*>> {
+            const SIZE: usize = crate::memory::Pointer::SIZE;
+            const ALIGN: usize = crate::memory::Pointer::ALIGN;
+
+            fn read(view: &ProcessMemoryView, address: u64) -> crate::Result<Self> {
+                let pointer: crate::memory::Pointer = view.read(address)?;
+                (pointer.address != 0).then(|| $ty::try_from(pointer)).transpose()
+            }
+
+            fn rebind(self, memory: &ProcessMemoryView) -> Self {
+                self.map(|value| value.rebind(memory))
+            }
+
+            fn type_name() -> String {
+                <$ty<$( $generic ),*> as crate::memory::MemoryRead>::type_name()
+            }
+
+            fn is_type(
+                value_type: &crate::memory::reflection::TypeRef,
+                registry: &crate::memory::ClassRegistry,
+            ) -> crate::Result<bool> {
+                <$ty<$( $generic ),*> as crate::memory::MemoryRead>::is_type(value_type, registry)
+            }
+        }
+
+        impl<$( $generic: MemoryRead ),*> From<$ty<$( $generic ),*>> for Object {
+            fn from(value: $ty<$( $generic ),*>) -> Object {
+                Object(value.0)
+            }
+        }
+
+        impl<$( $generic: MemoryRead ),*> From<$ty<$( $generic ),*>> for Pointer {
+            fn from(value: $ty<$( $generic ),*>) -> Pointer {
+                value.0
+            }
+        }
+
+        impl<$( $generic: MemoryRead ),*> TryFrom<Pointer> for $ty<$( $generic ),*> {
+            type Error = anyhow::Error;
+
+            fn try_from(value: Pointer) -> crate::Result<Self> {
+                let arguments: &[crate::memory::ArgumentCheck] = &[$( <$generic as crate::memory::MemoryRead>::is_type ),*];
+                let expected_type_name = crate::memory::class_name($name, arguments.len());
+
+                if value.address == 0 {
+                    anyhow::bail!(crate::memory::MemoryError::Null {
+                        address: 0,
+                        expected: expected_type_name,
+                    });
+                }
+
+                // immutable objects (and everything reachable from them) are read through the persistent cache
+                let value = match $immutable {
+                    true => value.persistent(),
+                    false => value,
+                };
+
+                let value = Self(value, std::default::Default::default());
+                let class = value.get_type()?;
+
+                if !class.is_assignable_to(&expected_type_name)? {
+                    anyhow::bail!(crate::memory::MemoryError::TypeMismatch {
+                        address: value.0.address,
+                        expected: expected_type_name,
+                        actual: class.get_name()?,
+                    });
+                }
+
+                // type arguments refer to their classes by type definition, which only the registry can resolve
+                if let Some(registry) = value.0.memory.registry().filter(|_| !arguments.is_empty()) {
+                    let binding = std::any::type_name::<Self>();
+                    let check = || crate::memory::is_class(&class, registry, &expected_type_name, arguments);
+
+                    if !registry.check_once(class.0.address, binding, check)? {
+                        anyhow::bail!(crate::memory::MemoryError::TypeMismatch {
+                            address: value.0.address,
+                            expected: <Self as crate::memory::MemoryRead>::type_name(),
+                            actual: class.get_type_ref().get_name(registry)?,
+                        });
+                    }
+                }
+
+                Ok(value)
+            }
+        }
+
+        impl<$( $generic: MemoryRead ),*> AsRef<Pointer> for $ty<$( $generic ),*> {
+            fn as_ref(&self) -> &Pointer {
+                &self.0
+            }
+        }
+
+        impl<$( $generic: MemoryRead ),*> crate::memory::ObjectPointer for $ty<$( $generic ),*> { }
+    };
+}
+
+pub(crate) use object_type;
+pub(crate) use pointer_type;
+
+/// A backend that `ProcessMemoryView` reads from, e.g. a live process or a set of in-memory byte regions
+pub trait MemorySource: Debug + Send + Sync {
+    /// reads up to `buffer.len()` bytes starting at `address`, returning the number of bytes read
+    fn read_memory(&self, address: u64, buffer: &mut [u8]) -> Result<usize>;
+
+    /// whether the memory can still be read at all, checked when a read fails
+    fn is_alive(&self) -> bool {
+        true
+    }
+}
+
+impl<T: MemorySource + ?Sized> MemorySource for Arc<T> {
+    fn read_memory(&self, address: u64, buffer: &mut [u8]) -> Result<usize> {
+        (**self).read_memory(address, buffer)
+    }
+
+    fn is_alive(&self) -> bool {
+        (**self).is_alive()
+    }
+}
+
+#[derive(Debug, Default)]
+struct ViewCaches {
+    /// pages read since the last `invalidate`, i.e. during the current poll
+    tick: PageCache,
+    /// pages of immutable objects (models, type info), kept until `invalidate_all`
+    persistent: PageCache,
+    /// strings read through persistent views, by address, kept until `invalidate_all`
+    strings: Mutex<HashMap<u64, String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessMemoryView {
+    source: Arc<dyn MemorySource>,
+    caches: Option<Arc<ViewCaches>>,
+    stats: Arc<MemoryStats>,
+    persistent: bool,
+    registry: Option<Arc<ClassRegistry>>,
+}
+
+impl ProcessMemoryView {
+    pub fn new(source: impl MemorySource + 'static) -> Self {
+        Self {
+            source: Arc::new(source),
+            caches: Some(Default::default()),
+            stats: Default::default(),
+            persistent: false,
+            registry: None,
+        }
+    }
+
+    /// a view sharing this view's caches whose objects have their classes checked against the registry
+    pub fn with_registry(&self, registry: Arc<ClassRegistry>) -> Self {
+        Self {
+            registry: Some(registry),
+            ..self.clone()
+        }
+    }
+
+    pub fn registry(&self) -> Option<&Arc<ClassRegistry>> {
+        self.registry.as_ref()
+    }
+
+    /// a view of the same source that always reads through to it
+    pub fn without_cache(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            caches: None,
+            stats: Default::default(),
+            persistent: false,
+            registry: self.registry.clone(),
+        }
+    }
+
+    /// a view sharing this view's caches whose reads are kept across `invalidate`, for objects that never change
+    pub fn persistent(&self) -> Self {
+        Self {
+            persistent: true,
+            ..self.clone()
+        }
+    }
+
+    /// a view sharing this view's caches whose reads are dropped on `invalidate`
+    pub fn transient(&self) -> Self {
+        Self {
+            persistent: false,
+            ..self.clone()
+        }
+    }
+
+    /// drops pages read during the current poll, should be called once per poll
+    pub fn invalidate(&self) {
+        if let Some(caches) = &self.caches {
+            caches.tick.clear();
+        }
+    }
+
+    /// drops all cached pages, e.g. when a new game is loaded
+    pub fn invalidate_all(&self) {
+        if let Some(caches) = &self.caches {
+            caches.tick.clear();
+            caches.persistent.clear();
+            caches.strings.lock().unwrap().clear();
+        }
+    }
+
+    /// reads a string, or reuses the one read from the same address if this view is persistent
+    pub fn read_cached_string(
+        &self,
+        address: u64,
+        read: impl FnOnce() -> Result<String>,
+    ) -> Result<String> {
+        let Some(caches) = self.caches.as_ref().filter(|_| self.persistent) else {
+            return read();
+        };
+
+        if let Some(value) = caches.strings.lock().unwrap().get(&address) {
+            return Ok(value.clone());
+        }
+
+        let value = read()?;
+        caches
+            .strings
+            .lock()
+            .unwrap()
+            .insert(address, value.clone());
+
+        Ok(value)
+    }
+
+    pub fn stats(&self) -> MemoryStatsReport {
+        self.stats.report()
+    }
+
+    pub fn reset_stats(&self) {
+        self.stats.reset();
+    }
+
+    pub fn read<T: MemoryRead>(&self, address: u64) -> Result<T> {
+        T::read(self, address)
+    }
+
+    /// reads a collection guarded by the `int` version counter at `version`, which the game bumps
+    /// on every modification, retrying if it changes during the read. `read` is given a view
+    /// without caches, since pages cached earlier in the poll may be older than the version, but
+    /// the poll's cache is left as it is for everything else
+    pub fn read_versioned<R>(
+        &self,
+        version: u64,
+        read: impl Fn(&ProcessMemoryView) -> Result<R>,
+    ) -> Result<R> {
+        let uncached = self.without_cache();
+
+        for _ in 0..=MAX_TORN_RETRIES {
+            let before: u32 = uncached.read(version)?;
+            let result = read(&uncached);
+            let after: u32 = uncached.read(version)?;
+
+            let torn = before != after;
+            self.stats.record_versioned_read(torn);
+
+            if !torn {
+                return result;
+            }
+        }
+
+        bail!(MemoryError::Torn { address: version })
+    }
+
+    pub fn read_bytes(&self, address: u64, buffer: &mut [u8]) -> Result<usize> {
+        self.stats.record_read();
+
+        let result = match &self.caches {
+            None => self.stats.read_source(&*self.source, address, buffer),
+
+            Some(caches) => {
+                let cache = match self.persistent {
+                    true => &caches.persistent,
+                    false => &caches.tick,
+                };
+
+                cache.read(&*self.source, &self.stats, address, buffer)
+            }
+        };
+
+        result.map_err(|e| match self.source.is_alive() {
+            true => e.context(MemoryError::Unreadable { address }),
+            false => MemoryError::ProcessGone.into(),
+        })
+    }
+
+    pub fn read_exact(&self, address: u64, out: &mut [u8]) -> Result<()> {
+        let mut index = 0;
+
+        while index < out.len() {
+            let count = self.read_bytes(address + index as u64, &mut out[index..])?;
+            if count == 0 {
+                bail!(MemoryError::Unreadable {
+                    address: address + index as u64
+                });
+            }
+
+            index += count;
+        }
+
+        Ok(())
+    }
+}
+
+/// offsets of a value type's fields, given each field's size and alignment, and the value type's
+/// size and alignment
+pub const fn sequential_layout<const N: usize>(
+    fields: [(usize, usize); N],
+) -> ([usize; N], usize, usize) {
+    let mut offsets = [0; N];
+    let mut offset: usize = 0;
+    let mut align = 1;
+
+    let mut i = 0;
+    while i < N {
+        let (size, field_align) = fields[i];
+
+        offsets[i] = offset.next_multiple_of(field_align);
+        offset = offsets[i] + size;
+        if field_align > align {
+            align = field_align;
+        }
+
+        i += 1;
+    }
+
+    (offsets, offset.next_multiple_of(align), align)
+}
+
+pub trait MemoryRead: Sized {
+    const SIZE: usize;
+    /// alignment when stored inline, e.g. in an array or a dictionary entry, which is that of the
+    /// largest primitive it holds
+    const ALIGN: usize;
+
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self>;
+
+    /// the value with any objects it refers to read through `memory` instead of the view it was
+    /// read with, e.g. to go back through the caches after a read that bypassed them
+    fn rebind(self, _memory: &ProcessMemoryView) -> Self {
+        self
+    }
+
+    /// the C# name of the type, for errors
+    fn type_name() -> String {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name).to_string()
+    }
+
+    /// whether values of an il2cpp type can be read as this type, to check generic type arguments
+    fn is_type(_value_type: &TypeRef, _registry: &ClassRegistry) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+impl MemoryRead for bool {
+    const SIZE: usize = 1;
+    const ALIGN: usize = 1;
+
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 1];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(buffer[0] != 0)
+    }
+}
+
+impl MemoryRead for f32 {
+    const SIZE: usize = 4;
+    const ALIGN: usize = 4;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 4];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(NativeEndian::read_f32(&buffer))
+    }
+}
+
+impl MemoryRead for f64 {
+    const SIZE: usize = 8;
+    const ALIGN: usize = 8;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 8];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(NativeEndian::read_f64(&buffer))
+    }
+}
+
+impl MemoryRead for u64 {
+    const SIZE: usize = 8;
+    const ALIGN: usize = 8;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 8];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(NativeEndian::read_u64(&buffer))
+    }
+}
+
+impl MemoryRead for u32 {
+    const SIZE: usize = 4;
+    const ALIGN: usize = 4;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 4];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(NativeEndian::read_u32(&buffer))
+    }
+}
+
+impl MemoryRead for i64 {
+    const SIZE: usize = 8;
+    const ALIGN: usize = 8;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 8];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(NativeEndian::read_i64(&buffer))
+    }
+}
+
+impl MemoryRead for i32 {
+    const SIZE: usize = 4;
+    const ALIGN: usize = 4;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 4];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(NativeEndian::read_i32(&buffer))
+    }
+}
+
+impl MemoryRead for u16 {
+    const SIZE: usize = 2;
+    const ALIGN: usize = 2;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 2];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(NativeEndian::read_u16(&buffer))
+    }
+}
+
+impl MemoryRead for i16 {
+    const SIZE: usize = 2;
+    const ALIGN: usize = 2;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 2];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(NativeEndian::read_i16(&buffer))
+    }
+}
+
+impl MemoryRead for u8 {
+    const SIZE: usize = 1;
+    const ALIGN: usize = 1;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 1];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(buffer[0])
+    }
+}
+
+impl MemoryRead for i8 {
+    const SIZE: usize = 1;
+    const ALIGN: usize = 1;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let mut buffer = [0; 1];
+        view.read_exact(address, &mut buffer)?;
+
+        Ok(buffer[0] as i8)
+    }
+}
+
+/// longest C string read before giving up on finding its terminator
+const MAX_C_STRING_LENGTH: usize = 1024;
+
+impl MemoryRead for String {
+    const SIZE: usize = 8;
+    const ALIGN: usize = 8;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let address: u64 = view.read(address)?;
+
+        // read up to the end of each page at a time, so a string ending just before an unmapped
+        // page can still be read
+        let mut buffer = vec![];
+        while buffer.len() < MAX_C_STRING_LENGTH {
+            let start = address + buffer.len() as u64;
+            let end = (start + 1).next_multiple_of(PAGE_SIZE);
+            let len = (end - start).min((MAX_C_STRING_LENGTH - buffer.len()) as u64);
+
+            let mut chunk = vec![0; len as usize];
+            view.read_exact(start, &mut chunk)?;
+
+            if let Some(len) = chunk.iter().position(|&b| b == 0) {
+                buffer.extend_from_slice(&chunk[..len]);
+                return Ok(String::from_utf8_lossy(&buffer).into_owned());
+            }
+
+            buffer.extend_from_slice(&chunk);
+        }
+
+        bail!(
+            "no null terminator in {} bytes at {:#x}",
+            MAX_C_STRING_LENGTH,
+            address
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Pointer {
+    pub memory: ProcessMemoryView,
+    pub address: u64,
+}
+
+impl Pointer {
+    pub fn read<T: MemoryRead>(&self, offset: u64) -> Result<T> {
+        self.memory.read(self.address + offset)
+    }
+
+    pub fn persistent(self) -> Pointer {
+        Pointer {
+            memory: self.memory.persistent(),
+            address: self.address,
+        }
+    }
+
+    pub fn transient(self) -> Pointer {
+        Pointer {
+            memory: self.memory.transient(),
+            address: self.address,
+        }
+    }
+}
+
+impl MemoryRead for Pointer {
+    const SIZE: usize = 8;
+    const ALIGN: usize = 8;
+    fn read(view: &ProcessMemoryView, address: u64) -> Result<Self> {
+        let address = view.read(address)?;
+
+        Ok(Self {
+            memory: view.clone(),
+            address,
+        })
+    }
+
+    fn rebind(self, memory: &ProcessMemoryView) -> Self {
+        Self {
+            memory: memory.clone(),
+            address: self.address,
+        }
+    }
+}
+
+pointer_type!(TypeInfo);
+impl TypeInfo {
+    /// looks up an initialized class by namespace and name, with nested classes named like `Outer.Inner`
+    pub fn find(registry: &ClassRegistry, namespace: &str, name: &str) -> Result<Option<TypeInfo>> {
+        registry.lookup(namespace, name)
+    }
+
+    pub fn get_name(&self) -> Result<String> {
+        self.0.read(0x10)
+    }
+
+    /// the name including any declaring types, as used by `find`
+    pub fn get_full_name(&self) -> Result<String> {
+        match self.get_declaring_type()? {
+            Some(declaring) => Ok(format!(
+                "{}.{}",
+                declaring.get_full_name()?,
+                self.get_name()?
+            )),
+            None => self.get_name(),
+        }
+    }
+
+    pub fn get_namespace(&self) -> Result<String> {
+        self.0.read(0x18)
+    }
+
+    pub fn get_statics(&self) -> Result<TypeStatics> {
+        // static field values change, unlike the class itself
+        let statics: TypeStatics = self.0.read(0xb8)?;
+        Ok(TypeStatics(statics.0.transient()))
+    }
+
+    pub fn get_declaring_type(&self) -> Result<Option<TypeInfo>> {
+        self.0.read(0x50)
+    }
+
+    pub fn get_base_type(&self) -> Result<Option<TypeInfo>> {
+        self.0.read(0x58)
+    }
+
+    pub fn is_assignable_to(&self, type_name: &str) -> Result<bool> {
+        Ok(self.find_base(type_name)?.is_some())
+    }
+
+    /// this class or the base class with the given name
+    pub fn find_base(&self, type_name: &str) -> Result<Option<TypeInfo>> {
+        match self.get_name()? == type_name {
+            true => Ok(Some(self.clone())),
+            false => match self.get_base_type()? {
+                Some(base) => base.find_base(type_name),
+                None => Ok(None),
+            },
+        }
+    }
+}
+
+/// the il2cpp name of a binding's class, e.g. ``List`1`` for `List<T>`
+pub fn class_name(name: &str, argument_count: usize) -> String {
+    match (name, argument_count) {
+        ("Array", _) | (_, 0) => name.to_string(),
+        (_, count) => format!("{}`{}", name, count),
+    }
+}
+
+/// checks one type argument of a generic binding, i.e. `MemoryRead::is_type`
+pub type ArgumentCheck = fn(&TypeRef, &ClassRegistry) -> Result<bool>;
+
+/// whether a class or one of its bases is the generic class `name` with type arguments accepted by
+/// `arguments`, where arrays are checked against their element class
+pub fn is_class(
+    class: &TypeInfo,
+    registry: &ClassRegistry,
+    name: &str,
+    arguments: &[ArgumentCheck],
+) -> Result<bool> {
+    let Some(base) = class.find_base(name)? else {
+        return Ok(false);
+    };
+
+    if arguments.is_empty() {
+        return Ok(true);
+    }
+
+    let actual = match name {
+        "Array" => match class.get_element_class()? {
+            Some(element) => vec![element.get_type_ref()],
+            None => vec![],
+        },
+        _ => base.get_generic_arguments()?,
+    };
+
+    // generic definitions have no arguments to compare
+    if actual.len() != arguments.len() {
+        return Ok(true);
+    }
+
+    for (argument, is_type) in actual.iter().zip(arguments) {
+        if !is_type(argument, registry)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// `is_class` for a type argument, which refers to its class by type definition
+pub fn is_type(
+    value_type: &TypeRef,
+    registry: &ClassRegistry,
+    name: &str,
+    arguments: &[ArgumentCheck],
+) -> Result<bool> {
+    match value_type.get_kind()? {
+        TypeKind::String => Ok(name == "String"),
+        TypeKind::SzArray | TypeKind::Array => Ok(name == "Array"),
+        // type parameters and object can't be narrowed down
+        TypeKind::Object | TypeKind::Var | TypeKind::MVar => Ok(true),
+
+        TypeKind::Class | TypeKind::ValueType | TypeKind::GenericInst => {
+            match value_type.get_class(registry)? {
+                Some(class) => is_class(&class, registry, name, arguments),
+                // classes that aren't initialized yet can't be checked
+                None => Ok(true),
+            }
+        }
+
+        _ => Ok(false),
+    }
+}
+
+pointer_type!(TypeStatics);
+impl TypeStatics {
+    pub fn field<T: MemoryRead>(&self, offset: u64) -> Result<T> {
+        self.0.read(offset)
+    }
+}
+
+pointer_type!(Object);
+impl TryFrom<Pointer> for Object {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Pointer) -> Result<Self> {
+        Ok(Self(value))
+    }
+}
+
+impl From<Object> for Pointer {
+    fn from(value: Object) -> Pointer {
+        value.0
+    }
+}
+
+impl AsRef<Pointer> for Object {
+    fn as_ref(&self) -> &Pointer {
+        &self.0
+    }
+}
+
+impl ObjectPointer for Object {}
+
+pub trait ObjectPointer:
+    Sized + TryFrom<Pointer, Error = anyhow::Error> + AsRef<Pointer> + Into<Pointer>
+{
+    fn cast<T: ObjectPointer>(self) -> Result<T> {
+        T::try_from(self.as_ref().clone())
+    }
+
+    fn get_type(&self) -> Result<TypeInfo> {
+        let type_info: TypeInfo = self.as_ref().read(0x0)?;
+        Ok(TypeInfo(type_info.0.persistent()))
+    }
+
+    unsafe fn field<T: MemoryRead>(&self, offset: u64) -> Result<T> {
+        self.as_ref().read(0x10 + offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// memory the game can modify while a view is reading it
+    #[derive(Debug)]
+    struct Live(Mutex<RegionMemory>);
+
+    impl MemorySource for Live {
+        fn read_memory(&self, address: u64, buffer: &mut [u8]) -> Result<usize> {
+            self.0.lock().unwrap().read_memory(address, buffer)
+        }
+    }
+
+    #[test]
+    fn versioned_reads_bypass_the_cache_without_clearing_it() {
+        const VERSION: u64 = 0x1000;
+        const ITEM: u64 = 0x2000;
+        const OTHER: u64 = 0x3000;
+
+        let live = Arc::new(Live(Mutex::new(
+            RegionMemory::new().with_region(0x1000, vec![0; 0x3000]),
+        )));
+        let view = ProcessMemoryView::new(live.clone());
+
+        // pages cached earlier in the poll, then the game modifies the collection and more
+        assert_eq!(view.read::<u32>(ITEM).unwrap(), 0);
+        assert_eq!(view.read::<u32>(OTHER).unwrap(), 0);
+        {
+            let mut memory = live.0.lock().unwrap();
+            memory.write(ITEM, &7u32.to_le_bytes()).unwrap();
+            memory.write(VERSION, &1u32.to_le_bytes()).unwrap();
+            memory.write(OTHER, &9u32.to_le_bytes()).unwrap();
+        }
+
+        let item = view.read_versioned(VERSION, |view| view.read::<u32>(ITEM));
+        assert_eq!(item.unwrap(), 7);
+
+        // the rest of the poll still reads the tick it started on
+        assert_eq!(view.read::<u32>(OTHER).unwrap(), 0);
+        view.invalidate();
+        assert_eq!(view.read::<u32>(OTHER).unwrap(), 9);
+    }
+}
@@ -0,0 +1,340 @@
+use std::fmt::Display;
+
+use super::{pointer_type, ClassRegistry, Pointer, ProcessMemoryView, TypeInfo};
+use crate::Result;
+
+/// `FIELD_ATTRIBUTE_STATIC` in `Il2CppType::attrs`
+const FIELD_ATTRIBUTE_STATIC: u32 = 0x10;
+/// `FIELD_ATTRIBUTE_LITERAL`, constants which have no storage at all
+const FIELD_ATTRIBUTE_LITERAL: u32 = 0x40;
+
+/// size of FieldInfo
+const FIELD_INFO_SIZE: u64 = 0x20;
+
+/// `Il2CppTypeEnum`, what kind of type an `Il2CppType` describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeKind {
+    Void,
+    Boolean,
+    Char,
+    I1,
+    U1,
+    I2,
+    U2,
+    I4,
+    U4,
+    I8,
+    U8,
+    R4,
+    R8,
+    String,
+    Ptr,
+    ValueType,
+    Class,
+    Var,
+    Array,
+    GenericInst,
+    I,
+    U,
+    Object,
+    SzArray,
+    MVar,
+    Other(u8),
+}
+
+impl From<u8> for TypeKind {
+    fn from(value: u8) -> Self {
+        match value {
+            0x01 => TypeKind::Void,
+            0x02 => TypeKind::Boolean,
+            0x03 => TypeKind::Char,
+            0x04 => TypeKind::I1,
+            0x05 => TypeKind::U1,
+            0x06 => TypeKind::I2,
+            0x07 => TypeKind::U2,
+            0x08 => TypeKind::I4,
+            0x09 => TypeKind::U4,
+            0x0a => TypeKind::I8,
+            0x0b => TypeKind::U8,
+            0x0c => TypeKind::R4,
+            0x0d => TypeKind::R8,
+            0x0e => TypeKind::String,
+            0x0f => TypeKind::Ptr,
+            0x11 => TypeKind::ValueType,
+            0x12 => TypeKind::Class,
+            0x13 => TypeKind::Var,
+            0x14 => TypeKind::Array,
+            0x15 => TypeKind::GenericInst,
+            0x18 => TypeKind::I,
+            0x19 => TypeKind::U,
+            0x1c => TypeKind::Object,
+            0x1d => TypeKind::SzArray,
+            0x1e => TypeKind::MVar,
+            v => TypeKind::Other(v),
+        }
+    }
+}
+
+impl TypeKind {
+    /// the C# keyword for built in types, as they appear in dump.cs
+    pub fn keyword(&self) -> Option<&'static str> {
+        match self {
+            TypeKind::Void => Some("void"),
+            TypeKind::Boolean => Some("bool"),
+            TypeKind::Char => Some("char"),
+            TypeKind::I1 => Some("sbyte"),
+            TypeKind::U1 => Some("byte"),
+            TypeKind::I2 => Some("short"),
+            TypeKind::U2 => Some("ushort"),
+            TypeKind::I4 => Some("int"),
+            TypeKind::U4 => Some("uint"),
+            TypeKind::I8 => Some("long"),
+            TypeKind::U8 => Some("ulong"),
+            TypeKind::R4 => Some("float"),
+            TypeKind::R8 => Some("double"),
+            TypeKind::String => Some("string"),
+            TypeKind::I => Some("IntPtr"),
+            TypeKind::U => Some("UIntPtr"),
+            TypeKind::Object => Some("object"),
+            _ => None,
+        }
+    }
+}
+
+impl Display for TypeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.keyword() {
+            Some(keyword) => write!(f, "{}", keyword),
+            None => write!(f, "{:?}", self),
+        }
+    }
+}
+
+pointer_type!(FieldInfo);
+impl FieldInfo {
+    pub fn get_name(&self) -> Result<String> {
+        self.0.read(0x0)
+    }
+
+    pub fn get_type(&self) -> Result<TypeRef> {
+        self.0.read(0x8)
+    }
+
+    /// the class declaring the field
+    pub fn get_parent(&self) -> Result<TypeInfo> {
+        self.0.read(0x10)
+    }
+
+    /// the offset from the start of the object, including its 16 byte header, or into the class statics
+    pub fn get_offset(&self) -> Result<i32> {
+        self.0.read(0x18)
+    }
+
+    pub fn is_static(&self) -> Result<bool> {
+        Ok(self.get_type()?.get_attributes()? & FIELD_ATTRIBUTE_STATIC != 0)
+    }
+
+    pub fn is_literal(&self) -> Result<bool> {
+        Ok(self.get_type()?.get_attributes()? & FIELD_ATTRIBUTE_LITERAL != 0)
+    }
+}
+
+// an `Il2CppType`, the type of a field or generic argument
+pointer_type!(TypeRef);
+impl TypeRef {
+    fn get_bits(&self) -> Result<u32> {
+        self.0.read(0x8)
+    }
+
+    pub fn get_attributes(&self) -> Result<u32> {
+        Ok(self.get_bits()? & 0xffff)
+    }
+
+    pub fn get_kind(&self) -> Result<TypeKind> {
+        Ok(TypeKind::from((self.get_bits()? >> 16) as u8))
+    }
+
+    pub fn is_byref(&self) -> Result<bool> {
+        Ok(self.get_bits()? & (1 << 29) != 0)
+    }
+
+    pub fn is_value_type(&self) -> Result<bool> {
+        Ok(self.get_bits()? & (1 << 31) != 0)
+    }
+
+    /// the class of a class or value type, looked up by its type definition
+    pub fn get_class(&self, registry: &ClassRegistry) -> Result<Option<TypeInfo>> {
+        match self.get_kind()? {
+            TypeKind::Class | TypeKind::ValueType => registry.lookup_handle(self.0.read(0x0)?),
+
+            TypeKind::GenericInst => {
+                let generic_class: Pointer = self.0.read(0x0)?;
+
+                match generic_class.read::<Option<TypeInfo>>(0x18)? {
+                    Some(class) => Ok(Some(class)),
+                    None => generic_class.read::<TypeRef>(0x0)?.get_class(registry),
+                }
+            }
+
+            _ => Ok(None),
+        }
+    }
+
+    /// the type of an array's elements
+    pub fn get_element_type(&self) -> Result<Option<TypeRef>> {
+        match self.get_kind()? {
+            TypeKind::SzArray | TypeKind::Ptr => Ok(Some(self.0.read(0x0)?)),
+            TypeKind::Array => Ok(Some(self.0.read::<Pointer>(0x0)?.read(0x0)?)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn get_generic_arguments(&self) -> Result<Vec<TypeRef>> {
+        match self.get_kind()? {
+            TypeKind::GenericInst => read_generic_inst(self.0.read(0x0)?),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// the type's name as dump.cs writes it, e.g. `List<Bloon>` or `int[]`
+    pub fn get_name(&self, registry: &ClassRegistry) -> Result<String> {
+        let kind = self.get_kind()?;
+        if let Some(keyword) = kind.keyword() {
+            return Ok(keyword.to_string());
+        }
+
+        match kind {
+            TypeKind::SzArray | TypeKind::Array | TypeKind::Ptr => {
+                let element = self.get_element_type()?.unwrap().get_name(registry)?;
+                let suffix = match kind {
+                    TypeKind::Ptr => "*",
+                    _ => "[]",
+                };
+
+                Ok(format!("{}{}", element, suffix))
+            }
+
+            TypeKind::Class | TypeKind::ValueType | TypeKind::GenericInst => {
+                let name = match self.get_class(registry)? {
+                    Some(class) => class.get_full_name()?,
+                    None => return Ok(format!("<{}>", kind)),
+                };
+
+                let name = strip_arity(&name);
+                let arguments = self.get_generic_arguments()?;
+                if arguments.is_empty() {
+                    return Ok(name);
+                }
+
+                let arguments = arguments
+                    .iter()
+                    .map(|a| a.get_name(registry))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(format!("{}<{}>", name, arguments.join(", ")))
+            }
+
+            _ => Ok(format!("<{}>", kind)),
+        }
+    }
+}
+
+impl TypeInfo {
+    /// the address of the class's type definition in global-metadata.dat
+    pub fn get_metadata_handle(&self) -> Result<u64> {
+        self.0.read(0x68)
+    }
+
+    /// the class's own `Il2CppType`, which names it including any generic arguments
+    pub fn get_type_ref(&self) -> TypeRef {
+        TypeRef(Pointer {
+            memory: self.0.memory.clone(),
+            address: self.0.address + 0x20,
+        })
+    }
+
+    /// the class of an array's elements, or the class itself for other types
+    pub fn get_element_class(&self) -> Result<Option<TypeInfo>> {
+        self.0.read(0x40)
+    }
+
+    /// the arguments of a generic class instance, e.g. `Bloon` for `List<Bloon>`
+    pub fn get_generic_arguments(&self) -> Result<Vec<TypeRef>> {
+        let generic_class: Pointer = self.0.read(0x60)?;
+
+        match generic_class.address {
+            0 => Ok(vec![]),
+            _ => read_generic_inst(generic_class),
+        }
+    }
+
+    /// the size of an instance, including the 16 byte object header
+    pub fn get_instance_size(&self) -> Result<u32> {
+        self.0.read(0xf8)
+    }
+
+    /// the stride of an array's elements
+    pub fn get_element_size(&self) -> Result<u32> {
+        self.0.read(0x104)
+    }
+
+    pub fn get_field_count(&self) -> Result<u16> {
+        let value: u32 = self.0.read(0x124)?;
+        Ok(value as u16)
+    }
+
+    /// the fields declared by this class, not including those of its base types
+    pub fn get_fields(&self) -> Result<Vec<FieldInfo>> {
+        let Some(fields) = self.0.read::<Option<FieldInfo>>(0x80)? else {
+            return Ok(vec![]);
+        };
+
+        let fields = (0..self.get_field_count()? as u64)
+            .map(|i| {
+                FieldInfo(Pointer {
+                    memory: fields.0.memory.clone(),
+                    address: fields.0.address + FIELD_INFO_SIZE * i,
+                })
+            })
+            .collect();
+
+        Ok(fields)
+    }
+
+    /// every instance field, including those of base types
+    pub fn get_instance_fields(&self) -> Result<Vec<FieldInfo>> {
+        let mut fields = match self.get_base_type()? {
+            Some(base) => base.get_instance_fields()?,
+            None => vec![],
+        };
+
+        for field in self.get_fields()? {
+            if !field.is_static()? && !field.is_literal()? {
+                fields.push(field);
+            }
+        }
+
+        Ok(fields)
+    }
+}
+
+/// reads the type arguments of the `Il2CppGenericInst` in an `Il2CppGenericClass`
+fn read_generic_inst(generic_class: Pointer) -> Result<Vec<TypeRef>> {
+    let inst: Pointer = generic_class.read(0x8)?;
+    if inst.address == 0 {
+        return Ok(vec![]);
+    }
+
+    let count: u32 = inst.read(0x0)?;
+    let arguments: Pointer = inst.read(0x8)?;
+
+    (0..count as u64).map(|i| arguments.read(8 * i)).collect()
+}
+
+/// `Dictionary`2.Entry` to `Dictionary.Entry`
+fn strip_arity(name: &str) -> String {
+    name.split('.')
+        .map(|part| part.split_once('`').map_or(part, |(part, _)| part))
+        .collect::<Vec<_>>()
+        .join(".")
+}
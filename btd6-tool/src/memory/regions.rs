@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use anyhow::bail;
+
+use super::MemorySource;
+use crate::Result;
+
+/// A memory source backed by byte regions held in memory, e.g. a synthetic heap
+///
+/// Where regions overlap, the one starting at the highest address is the one mapped.
+#[derive(Debug, Clone, Default)]
+pub struct RegionMemory {
+    regions: BTreeMap<u64, Vec<u8>>,
+}
+
+impl RegionMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(test)]
+    pub fn with_region(mut self, address: u64, data: impl Into<Vec<u8>>) -> Self {
+        self.insert(address, data);
+        self
+    }
+
+    /// maps `data` at `address`, replacing any region that starts at the same address
+    pub fn insert(&mut self, address: u64, data: impl Into<Vec<u8>>) {
+        self.regions.insert(address, data.into());
+    }
+
+    /// overwrites bytes inside an existing region
+    #[cfg(test)]
+    pub fn write(&mut self, address: u64, data: &[u8]) -> Result<()> {
+        let Some((start, end)) = self.find(address) else {
+            bail!("address not mapped: {:#x}", address);
+        };
+
+        if address + data.len() as u64 > end {
+            bail!("write out of bounds: {:#x}", address);
+        }
+
+        let offset = (address - start) as usize;
+        let region = self.regions.get_mut(&start).unwrap();
+        region[offset..offset + data.len()].copy_from_slice(data);
+
+        Ok(())
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = (u64, &[u8])> {
+        self.regions.iter().map(|(&k, v)| (k, v.as_slice()))
+    }
+
+    /// the start of the region mapped at `address`, and where its mapping ends, which is early
+    /// if a later region starts inside it
+    fn find(&self, address: u64) -> Option<(u64, u64)> {
+        let (&start, region) = self
+            .regions
+            .range(..=address)
+            .rev()
+            .find(|(&start, region)| address - start < region.len() as u64)?;
+
+        let end = match self.regions.range(address + 1..).next() {
+            Some((&next, _)) => next.min(start + region.len() as u64),
+            None => start + region.len() as u64,
+        };
+
+        Some((start, end))
+    }
+}
+
+impl MemorySource for RegionMemory {
+    fn read_memory(&self, address: u64, buffer: &mut [u8]) -> Result<usize> {
+        let Some((start, end)) = self.find(address) else {
+            bail!("address not mapped: {:#x}", address);
+        };
+
+        let offset = (address - start) as usize;
+        let count = buffer.len().min((end - address) as usize);
+        buffer[..count].copy_from_slice(&self.regions[&start][offset..offset + count]);
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_through_overlapping_regions() {
+        let memory = RegionMemory::new()
+            .with_region(0x1000, vec![1; 0x3000])
+            .with_region(0x2000, vec![2; 0x10]);
+
+        let mut buffer = [0; 0x20];
+
+        // the later region covers its own bytes only
+        assert_eq!(memory.read_memory(0x1ff0, &mut buffer).unwrap(), 0x10);
+        assert_eq!(buffer[..0x10], [1; 0x10]);
+
+        assert_eq!(memory.read_memory(0x2000, &mut buffer).unwrap(), 0x10);
+        assert_eq!(buffer[..0x10], [2; 0x10]);
+
+        // past its end, the earlier region is mapped again
+        assert_eq!(memory.read_memory(0x2800, &mut buffer).unwrap(), 0x20);
+        assert_eq!(buffer, [1; 0x20]);
+
+        assert!(memory.read_memory(0x4000, &mut buffer).is_err());
+        assert!(memory.read_memory(0x800, &mut buffer).is_err());
+    }
+
+    #[test]
+    fn writes_inside_regions() {
+        let mut memory = RegionMemory::new()
+            .with_region(0x1000, vec![0; 0x3000])
+            .with_region(0x2000, vec![0; 0x10]);
+
+        memory.write(0x2800, &[7; 4]).unwrap();
+        memory.write(0x2008, &[9; 8]).unwrap();
+
+        assert!(memory.write(0x200c, &[0; 8]).is_err());
+        assert!(memory.write(0x3ffe, &[0; 4]).is_err());
+
+        let mut buffer = [0; 4];
+        memory.read_memory(0x2800, &mut buffer).unwrap();
+        assert_eq!(buffer, [7; 4]);
+
+        memory.read_memory(0x200c, &mut buffer).unwrap();
+        assert_eq!(buffer, [9; 4]);
+    }
+}
@@ -0,0 +1,340 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use btd6_tool_metadata::MetadataRegistration;
+use byteorder::{ByteOrder, NativeEndian};
+
+use super::{scan::ModuleImage, Pointer, ProcessMemoryView, TypeInfo};
+use crate::{offsets, Result};
+
+/// offset of `typeMetadataHandle` in Il2CppClass
+const CLASS_METADATA_HANDLE: usize = 0x68;
+/// offset of `klass` in Il2CppClass, which points back to the class itself
+const CLASS_SELF: usize = 0x78;
+const CLASS_HEADER_SIZE: usize = 0x80;
+
+/// how many leading entries of a candidate table are checked when looking for the class table
+const SAMPLE_SIZE: usize = 64;
+const MIN_SAMPLE_CLASSES: usize = 8;
+
+/// how often a failed lookup may re-read the table for classes initialized since
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// where each build's class table and type definition count were found, so attaching to the same
+/// build again only has to check them
+static LOCATED: Mutex<BTreeMap<String, Located>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Copy)]
+struct Located {
+    count: usize,
+    /// offset from the module base of the pointer to the class table
+    table_slot: u64,
+}
+
+/// The classes il2cpp has initialized, by namespace and name, read from its table of classes
+/// indexed by type definition
+#[derive(Debug)]
+pub struct ClassRegistry {
+    memory: ProcessMemoryView,
+    table: u64,
+    count: usize,
+    state: Mutex<RegistryState>,
+    /// (class, binding) pairs whose generic type arguments have been checked
+    verified: Mutex<HashSet<(u64, &'static str)>>,
+}
+
+#[derive(Debug, Default)]
+struct RegistryState {
+    classes: HashMap<(String, String), u64>,
+    /// classes by the address of their type definition, which is how `Il2CppType` refers to them
+    handles: HashMap<u64, u64>,
+    seen: HashSet<u64>,
+    last_refresh: Option<Instant>,
+}
+
+impl ClassRegistry {
+    /// finds the class table in a loaded GameAssembly.dll, sized by the type definition count
+    /// in its metadata registration, scanning its sections only the first time a build is seen
+    pub fn locate(memory: &ProcessMemoryView, module_base: u64) -> Result<Self> {
+        let uncached = memory.without_cache();
+        let image = ModuleImage::read(&uncached, module_base)?;
+        let build_id = offsets::build_id(image.headers.timestamp);
+
+        let cached = LOCATED.lock().unwrap().get(&build_id).copied();
+
+        // the table itself is allocated at startup, so only the slot holding it carries over
+        let table = cached
+            .and_then(|located| uncached.read(module_base + located.table_slot).ok())
+            .filter(|&table| is_class_table(&uncached, table));
+
+        let (located, table) = match (cached, table) {
+            (Some(located), Some(table)) => (located, table),
+            _ => {
+                let (located, table) = Self::scan(&uncached, &image)?;
+                LOCATED.lock().unwrap().insert(build_id, located);
+                (located, table)
+            }
+        };
+
+        Ok(Self {
+            memory: memory.persistent(),
+            table,
+            count: located.count,
+            state: Default::default(),
+            verified: Default::default(),
+        })
+    }
+
+    /// finds the metadata registration and the slot pointing to the class table in the image's
+    /// data sections
+    fn scan(memory: &ProcessMemoryView, image: &ModuleImage) -> Result<(Located, u64)> {
+        let sections = image
+            .data_sections()
+            .map(|s| {
+                Ok((
+                    image.base + s.virtual_address as u64,
+                    image.read_section(memory, s)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let count = sections
+            .iter()
+            .find_map(|(address, data)| {
+                MetadataRegistration::find_iter(data, *address, |p| image.contains(p)).next()
+            })
+            .context("metadata registration not found")?
+            .type_definition_count();
+
+        let (slot, table) = sections
+            .iter()
+            .find_map(|(address, data)| find_class_table(memory, image, *address, data))
+            .context("class table not found")?;
+
+        let located = Located {
+            count,
+            table_slot: slot - image.base,
+        };
+
+        Ok((located, table))
+    }
+
+    /// runs a check of a class against a binding, skipping it once it has passed
+    pub fn check_once(
+        &self,
+        class: u64,
+        binding: &'static str,
+        check: impl FnOnce() -> Result<bool>,
+    ) -> Result<bool> {
+        if self.verified.lock().unwrap().contains(&(class, binding)) {
+            return Ok(true);
+        }
+
+        let passed = check()?;
+        if passed {
+            self.verified.lock().unwrap().insert((class, binding));
+        }
+
+        Ok(passed)
+    }
+
+    pub(super) fn lookup(&self, namespace: &str, name: &str) -> Result<Option<TypeInfo>> {
+        let key = (namespace.to_string(), name.to_string());
+
+        self.get_or_refresh(|state| state.classes.get(&key).copied())
+    }
+
+    pub(super) fn lookup_handle(&self, handle: u64) -> Result<Option<TypeInfo>> {
+        self.get_or_refresh(|state| state.handles.get(&handle).copied())
+    }
+
+    fn get_or_refresh(
+        &self,
+        get: impl Fn(&RegistryState) -> Option<u64>,
+    ) -> Result<Option<TypeInfo>> {
+        let mut state = self.state.lock().unwrap();
+
+        if get(&state).is_none()
+            && state
+                .last_refresh
+                .is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL)
+        {
+            self.refresh(&mut state)?;
+        }
+
+        Ok(get(&state).map(|address| self.get_class(address)))
+    }
+
+    /// reads any classes initialized since the last refresh
+    fn refresh(&self, state: &mut RegistryState) -> Result<()> {
+        state.last_refresh = Some(Instant::now());
+
+        let mut table = vec![0; self.count * 8];
+        self.memory
+            .without_cache()
+            .read_exact(self.table, &mut table)?;
+
+        for entry in table.chunks_exact(8) {
+            let address = NativeEndian::read_u64(entry);
+            if address == 0 || state.seen.contains(&address) {
+                continue;
+            }
+
+            // a class still being initialized is retried on the next refresh
+            let Ok((key, handle)) = get_key(&self.get_class(address)) else {
+                continue;
+            };
+
+            state.seen.insert(address);
+            state.classes.entry(key).or_insert(address);
+            state.handles.insert(handle, address);
+        }
+
+        Ok(())
+    }
+
+    fn get_class(&self, address: u64) -> TypeInfo {
+        TypeInfo(Pointer {
+            memory: self.memory.clone(),
+            address,
+        })
+    }
+}
+
+fn get_key(class: &TypeInfo) -> Result<((String, String), u64)> {
+    let name = (class.get_namespace()?, class.get_full_name()?);
+
+    Ok((name, class.get_metadata_handle()?))
+}
+
+/// finds the slot holding `s_TypeInfoDefinitionTable`, and the table, by the classes in its first
+/// entries, which point back to themselves and have metadata handles an equal stride apart
+fn find_class_table(
+    memory: &ProcessMemoryView,
+    image: &ModuleImage,
+    address: u64,
+    data: &[u8],
+) -> Option<(u64, u64)> {
+    data.chunks_exact(8)
+        .enumerate()
+        .map(|(i, slot)| (address + 8 * i as u64, NativeEndian::read_u64(slot)))
+        .filter(|&(_, v)| v >= 0x10000 && v % 8 == 0 && !image.contains(v))
+        .find(|&(_, table)| is_class_table(memory, table))
+}
+
+fn is_class_table(memory: &ProcessMemoryView, table: u64) -> bool {
+    let mut sample = [0; SAMPLE_SIZE * 8];
+    if memory.read_exact(table, &mut sample).is_err() {
+        return false;
+    }
+
+    let mut handles = vec![];
+    for (index, entry) in sample.chunks_exact(8).enumerate() {
+        let address = NativeEndian::read_u64(entry);
+        if address == 0 {
+            continue;
+        }
+
+        let mut class = [0; CLASS_HEADER_SIZE];
+        if memory.read_exact(address, &mut class).is_err()
+            || NativeEndian::read_u64(&class[CLASS_SELF..]) != address
+        {
+            return false;
+        }
+
+        let handle = NativeEndian::read_u64(&class[CLASS_METADATA_HANDLE..]);
+        handles.push((index as u64, handle));
+    }
+
+    if handles.len() < MIN_SAMPLE_CLASSES {
+        return false;
+    }
+
+    let (first_index, first_handle) = handles[0];
+    let (second_index, second_handle) = handles[1];
+    let Some(stride) = second_handle
+        .checked_sub(first_handle)
+        .map(|d| d / (second_index - first_index))
+        .filter(|&stride| stride > 0)
+    else {
+        return false;
+    };
+
+    handles
+        .iter()
+        .all(|&(index, handle)| handle == first_handle + stride * (index - first_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{Heap, CLASS_TABLE_SLOT, MODULE, REGISTRATION, TYPE_DEFINITIONS};
+
+    const CLASS_COUNT: usize = 16;
+
+    /// a module with a metadata registration and a class table with 16 classes initialized
+    fn registered_heap() -> Heap {
+        let mut h = Heap::new();
+
+        // a heap pointer that isn't the class table comes first
+        let decoy = h.alloc(SAMPLE_SIZE as u64 * 8);
+        h.write_u64(MODULE + 0x2008, decoy);
+
+        for i in 0..CLASS_COUNT {
+            h.class_in("Assets.Scripts.Models", &format!("Model{}", i), None);
+        }
+
+        h.register_classes();
+        h
+    }
+
+    #[test]
+    fn locates_the_class_table_and_finds_classes() {
+        let h = registered_heap();
+        let view = h.view();
+        let registry = ClassRegistry::locate(&view, MODULE).unwrap();
+
+        assert_eq!(registry.count, TYPE_DEFINITIONS as usize);
+        assert_eq!(registry.table, view.read::<u64>(CLASS_TABLE_SLOT).unwrap());
+
+        let class = registry
+            .lookup("Assets.Scripts.Models", "Model3")
+            .unwrap()
+            .unwrap();
+        assert_eq!(class.get_name().unwrap(), "Model3");
+
+        let handle = class.get_metadata_handle().unwrap();
+        assert_eq!(
+            registry.lookup_handle(handle).unwrap().unwrap().0.address,
+            class.0.address
+        );
+
+        assert!(registry
+            .lookup("Assets.Scripts.Models", "Missing")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn reuses_where_a_build_keeps_its_class_table() {
+        let mut h = registered_heap();
+        ClassRegistry::locate(&h.view(), MODULE).unwrap();
+
+        // another process of the same build, with its class table allocated elsewhere and its
+        // registration out of reach of a scan
+        let table = h.register_classes();
+        h.write(REGISTRATION, &[0; 16 * 7]);
+
+        let registry = ClassRegistry::locate(&h.view(), MODULE).unwrap();
+        assert_eq!(registry.table, table);
+        assert_eq!(registry.count, TYPE_DEFINITIONS as usize);
+
+        // without a table in the slot, it falls back to scanning, which finds nothing
+        h.write_u64(CLASS_TABLE_SLOT, 0);
+        assert!(ClassRegistry::locate(&h.view(), MODULE).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use btd6_tool_metadata::pe::{PeHeaders, Section};
+
+use super::{ProcessMemoryView, PAGE_SIZE};
+use crate::Result;
+
+/// A byte pattern like `48 8B 05 ?? ?? ?? ??`, where `??` matches any byte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    bytes: Vec<Option<u8>>,
+}
+
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bytes = s
+            .split_whitespace()
+            .map(|byte| match byte {
+                "?" | "??" => Ok(None),
+                _ => Ok(Some(
+                    u8::from_str_radix(byte, 16).context("invalid pattern byte")?,
+                )),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if bytes.first().is_none_or(Option::is_none) {
+            bail!("pattern must start with a known byte");
+        }
+
+        Ok(Self { bytes })
+    }
+}
+
+impl Pattern {
+    pub fn matches(&self, data: &[u8]) -> bool {
+        data.len() >= self.bytes.len()
+            && self
+                .bytes
+                .iter()
+                .zip(data)
+                .all(|(p, b)| p.is_none_or(|p| p == *b))
+    }
+
+    /// the position of every match in `data`
+    pub fn find_iter<'a>(&'a self, data: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let first = self.bytes[0].unwrap();
+        let end = (data.len() + 1).saturating_sub(self.bytes.len());
+
+        (0..end).filter(move |&i| data[i] == first && self.matches(&data[i..]))
+    }
+}
+
+/// An instruction with a RIP-relative operand, e.g. `mov rax, [rip+disp32]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub pattern: Pattern,
+    /// position of the 32-bit displacement within the pattern
+    pub displacement: usize,
+    /// length of the instruction holding the displacement, which it is relative to the end of
+    pub instruction_len: usize,
+}
+
+impl Signature {
+    pub fn new(pattern: &str, displacement: usize, instruction_len: usize) -> Result<Self> {
+        let pattern = pattern.parse()?;
+
+        Ok(Self {
+            pattern,
+            displacement,
+            instruction_len,
+        })
+    }
+
+    /// the addresses referenced by each match, where `data` starts at `address`
+    pub fn resolve_iter<'a>(
+        &'a self,
+        data: &'a [u8],
+        address: u64,
+    ) -> impl Iterator<Item = u64> + 'a {
+        self.pattern.find_iter(data).filter_map(move |position| {
+            let start = position + self.displacement;
+            let displacement = i32::from_le_bytes(data.get(start..start + 4)?.try_into().ok()?);
+
+            let next = address + (position + self.instruction_len) as u64;
+            Some(next.wrapping_add_signed(displacement as i64))
+        })
+    }
+}
+
+/// The sections of a module loaded in memory, for scanning its code
+pub struct ModuleImage {
+    pub base: u64,
+    pub headers: PeHeaders,
+}
+
+impl ModuleImage {
+    pub fn read(memory: &ProcessMemoryView, base: u64) -> Result<Self> {
+        let mut page = vec![0; PAGE_SIZE as usize];
+        memory.read_exact(base, &mut page)?;
+
+        let headers = PeHeaders::parse(&page)?;
+
+        Ok(Self { base, headers })
+    }
+
+    pub fn code_sections(&self) -> impl Iterator<Item = &Section> {
+        self.headers.sections.iter().filter(|s| s.is_code())
+    }
+
+    pub fn data_sections(&self) -> impl Iterator<Item = &Section> {
+        self.headers.sections.iter().filter(|s| s.is_data())
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        address
+            .checked_sub(self.base)
+            .is_some_and(|rva| self.headers.find_section(rva).is_some())
+    }
+
+    pub fn is_data(&self, address: u64) -> bool {
+        address
+            .checked_sub(self.base)
+            .and_then(|rva| self.headers.find_section(rva))
+            .is_some_and(Section::is_data)
+    }
+
+    /// reads a section in full, bypassing the page cache since it is only scanned once
+    pub fn read_section(&self, memory: &ProcessMemoryView, section: &Section) -> Result<Vec<u8>> {
+        let mut data = vec![0; section.virtual_size as usize];
+        memory
+            .without_cache()
+            .read_exact(self.base + section.virtual_address as u64, &mut data)?;
+
+        Ok(data)
+    }
+}
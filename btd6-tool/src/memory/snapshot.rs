@@ -0,0 +1,170 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+use anyhow::{bail, Context};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use super::{MemorySource, ProcessMemoryView, RegionMemory, PAGE_SIZE};
+use crate::Result;
+
+pub const MAGIC: &[u8; 8] = b"BTD6SNAP";
+const VERSION: u32 = 2;
+
+/// Memory captured from a live game, along with the roots needed to find `InGame` again and the
+/// build it was captured from, whose offsets it has to be read with.
+///
+/// File layout (little endian):
+/// `magic[8] version:u32 build_id_len:u32 build_id[build_id_len] module_base:u64
+/// ingame_type_offset:u64 region_count:u32`
+/// followed by `region_count` entries of `address:u64 len:u32 data[len]`
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub build_id: String,
+    pub module_base: u64,
+    pub ingame_type_offset: u64,
+    pub memory: RegionMemory,
+}
+
+impl Snapshot {
+    pub fn load(path: impl AsRef<Path>) -> Result<Snapshot> {
+        let mut file = BufReader::new(File::open(path)?);
+
+        let mut magic = [0; 8];
+        file.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            bail!("not a snapshot file");
+        }
+
+        let version = file.read_u32::<LittleEndian>()?;
+        if version != VERSION {
+            bail!("unsupported snapshot version {version}, expected {VERSION}");
+        }
+
+        let mut build_id = vec![0; file.read_u32::<LittleEndian>()? as usize];
+        file.read_exact(&mut build_id)?;
+        let build_id = String::from_utf8(build_id).context("invalid build id")?;
+
+        let module_base = file.read_u64::<LittleEndian>()?;
+        let ingame_type_offset = file.read_u64::<LittleEndian>()?;
+
+        let mut memory = RegionMemory::new();
+
+        for _ in 0..file.read_u32::<LittleEndian>()? {
+            let address = file.read_u64::<LittleEndian>()?;
+            let len = file.read_u32::<LittleEndian>()?;
+
+            let mut data = vec![0; len as usize];
+            file.read_exact(&mut data)?;
+
+            memory.insert(address, data);
+        }
+
+        Ok(Snapshot {
+            build_id,
+            module_base,
+            ingame_type_offset,
+            memory,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+
+        file.write_all(MAGIC)?;
+        file.write_u32::<LittleEndian>(VERSION)?;
+        file.write_u32::<LittleEndian>(self.build_id.len() as u32)?;
+        file.write_all(self.build_id.as_bytes())?;
+        file.write_u64::<LittleEndian>(self.module_base)?;
+        file.write_u64::<LittleEndian>(self.ingame_type_offset)?;
+
+        file.write_u32::<LittleEndian>(self.memory.regions().count() as u32)?;
+        for (address, data) in self.memory.regions() {
+            file.write_u64::<LittleEndian>(address)?;
+            file.write_u32::<LittleEndian>(data.len() as u32)?;
+            file.write_all(data)?;
+        }
+
+        file.flush()?;
+
+        Ok(())
+    }
+}
+
+/// A memory source that passes reads through to another view and keeps a copy of every page touched
+#[derive(Debug)]
+pub struct SnapshotRecorder {
+    inner: ProcessMemoryView,
+    build_id: String,
+    module_base: u64,
+    ingame_type_offset: u64,
+    pages: Mutex<BTreeMap<u64, Vec<u8>>>,
+}
+
+impl SnapshotRecorder {
+    pub fn new(
+        inner: ProcessMemoryView,
+        build_id: String,
+        module_base: u64,
+        ingame_type_offset: u64,
+    ) -> Self {
+        Self {
+            inner,
+            build_id,
+            module_base,
+            ingame_type_offset,
+            pages: Default::default(),
+        }
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.lock().unwrap().len()
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        let mut memory = RegionMemory::new();
+
+        for (&address, data) in self.pages.lock().unwrap().iter() {
+            memory.insert(address, data.clone());
+        }
+
+        Snapshot {
+            build_id: self.build_id.clone(),
+            module_base: self.module_base,
+            ingame_type_offset: self.ingame_type_offset,
+            memory,
+        }
+    }
+}
+
+impl MemorySource for SnapshotRecorder {
+    fn read_memory(&self, address: u64, buffer: &mut [u8]) -> Result<usize> {
+        let mut pages = self.pages.lock().unwrap();
+
+        let mut index = 0;
+        while index < buffer.len() {
+            let current = address + index as u64;
+            let page_address = current & !(PAGE_SIZE - 1);
+
+            let page = match pages.entry(page_address) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let mut page = vec![0; PAGE_SIZE as usize];
+                    self.inner.read_exact(page_address, &mut page)?;
+                    entry.insert(page)
+                }
+            };
+            let offset = (current - page_address) as usize;
+            let count = (buffer.len() - index).min(page.len() - offset);
+
+            buffer[index..index + count].copy_from_slice(&page[offset..offset + count]);
+            index += count;
+        }
+
+        Ok(index)
+    }
+}
@@ -0,0 +1,400 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::File,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc, OnceLock, RwLock,
+    },
+};
+
+use anyhow::{bail, Context};
+use btd6_tool_dump::{full_name, ClassLayouts, Script};
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// overrides where offsets.json is read from and written to
+pub const PATH_VARIABLE: &str = "BTD6_OFFSETS";
+
+/// offsets.json next to the executable, so running the tool from another directory finds the same
+/// offsets, unless `BTD6_OFFSETS` gives another path
+pub fn default_path() -> Result<PathBuf> {
+    if let Some(path) = env::var_os(PATH_VARIABLE) {
+        return Ok(path.into());
+    }
+
+    let executable = env::current_exe()?;
+    let directory = executable.parent().context("executable has no directory")?;
+
+    Ok(directory.join("offsets.json"))
+}
+
+/// Offsets for every game build we have generated them for, keyed by `build_id`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OffsetsFile {
+    pub builds: BTreeMap<String, BuildOffsets>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildOffsets {
+    /// offsets of each class's `TypeInfo` pointer from the base of GameAssembly.dll
+    pub type_info: BTreeMap<String, u64>,
+
+    /// field offsets (after the object header) by class full name and field name
+    pub fields: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+impl OffsetsFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_reader(File::open(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        serde_json::to_writer_pretty(File::create(path)?, self)?;
+
+        Ok(())
+    }
+}
+
+impl BuildOffsets {
+    /// looks up every field bound by `btd6_tool_bindgen::class`, and their classes' `TypeInfo`s
+    /// from a script.json if one is given or else the layouts, along with why each field that
+    /// couldn't be found is missing
+    pub fn generate(layouts: &dyn ClassLayouts, script: Option<&Script>) -> (Self, Vec<String>) {
+        let mut offsets = BuildOffsets::default();
+        let mut missing = vec![];
+
+        for FieldKey(field) in inventory::iter::<FieldKey> {
+            let class_name = field.class_name();
+
+            let type_info = match script {
+                Some(script) => script.get_type_info(&class_name),
+                None => layouts.get_type_info(field.namespace, field.class),
+            };
+
+            if let Some(type_info) = type_info {
+                offsets.type_info.insert(class_name.clone(), type_info);
+            }
+
+            // the rest of the bindings still work without it
+            let offset = match layouts.get_field_offset(field.namespace, field.class, field.field) {
+                Ok(offset) => offset as u64,
+                Err(e) => {
+                    missing.push(format!("{}.{}: {:#}", class_name, field.field, e));
+                    continue;
+                }
+            };
+
+            offsets
+                .fields
+                .entry(class_name)
+                .or_default()
+                .insert(field.field.to_string(), offset);
+        }
+
+        missing.sort();
+        missing.dedup();
+
+        (offsets, missing)
+    }
+
+    fn get_field(&self, class_name: &str, field: &str) -> Option<u64> {
+        self.fields.get(class_name)?.get(field).copied()
+    }
+}
+
+/// identifies a build of GameAssembly.dll by the link timestamp in its PE header
+pub fn build_id(timestamp: u32) -> String {
+    format!("{:08x}", timestamp)
+}
+
+/// the game version of each build with a dump in `btd6-tool-bindgen/dumps`, by `build_id`
+static DUMPED_BUILDS: &[(&str, &str)] = btd6_tool_bindgen::game_builds!();
+
+static LOADED: OnceLock<OffsetsFile> = OnceLock::new();
+
+static ACTIVE: RwLock<Option<Arc<BuildOffsets>>> = RwLock::new(None);
+
+/// the dumped version of the active build, or None to use the newest dump
+static ACTIVE_VERSION: RwLock<Option<&'static str>> = RwLock::new(None);
+
+/// bumped whenever `ACTIVE` changes, so each field knows to look up its offset again
+static GENERATION: AtomicU32 = AtomicU32::new(0);
+
+/// loads the offsets file used by `select`, if one exists
+pub fn init(path: impl AsRef<Path>) -> Result<()> {
+    if !path.as_ref().exists() {
+        return Ok(());
+    }
+
+    let file = OffsetsFile::load(path)?;
+    LOADED.set(file).ok().context("offsets already loaded")?;
+
+    Ok(())
+}
+
+/// whether offsets.json or a dump has offsets for the given build
+pub fn is_known(build_id: &str) -> bool {
+    LOADED
+        .get()
+        .is_some_and(|file| file.builds.contains_key(build_id))
+        || DUMPED_BUILDS.iter().any(|(build, _)| *build == build_id)
+}
+
+/// switches field offsets to those of the given build, from offsets.json or else the build's dump,
+/// or to the newest dump if it is unknown
+pub fn select(build_id: Option<&str>) -> Option<Arc<BuildOffsets>> {
+    let offsets = build_id
+        .zip(LOADED.get())
+        .and_then(|(id, file)| file.builds.get(id))
+        .map(|offsets| Arc::new(offsets.clone()));
+
+    let version = build_id
+        .and_then(|id| DUMPED_BUILDS.iter().find(|(build, _)| *build == id))
+        .map(|(_, version)| *version);
+
+    *ACTIVE.write().unwrap() = offsets.clone();
+    *ACTIVE_VERSION.write().unwrap() = version;
+    GENERATION.fetch_add(1, Ordering::Release);
+
+    offsets
+}
+
+/// The offset of one bound field, resolved through the active build's offsets
+#[derive(Debug)]
+pub struct FieldOffset {
+    namespace: &'static str,
+    class: &'static str,
+    field: &'static str,
+    /// the name of the Rust accessor bound to the field
+    accessor: &'static str,
+    /// the offset in each dumped version of the game that has the field, newest first
+    versions: &'static [(&'static str, u64)],
+
+    offset: AtomicU64,
+    generation: AtomicU32,
+}
+
+/// cached in place of an offset for a field the active build doesn't have
+const MISSING: u64 = u64::MAX;
+
+impl FieldOffset {
+    pub const fn new(
+        namespace: &'static str,
+        class: &'static str,
+        field: &'static str,
+        accessor: &'static str,
+        versions: &'static [(&'static str, u64)],
+    ) -> Self {
+        Self {
+            namespace,
+            class,
+            field,
+            accessor,
+            versions,
+            offset: AtomicU64::new(MISSING),
+            // never current, so the first `get` looks the offset up
+            generation: AtomicU32::new(u32::MAX),
+        }
+    }
+
+    pub fn class_name(&self) -> String {
+        full_name(self.namespace, self.class)
+    }
+
+    pub fn get(&self) -> Result<u64> {
+        let generation = GENERATION.load(Ordering::Acquire);
+
+        let offset = match self.generation.load(Ordering::Acquire) == generation {
+            true => self.offset.load(Ordering::Relaxed),
+            false => {
+                let offset = self.resolve().unwrap_or(MISSING);
+
+                self.offset.store(offset, Ordering::Relaxed);
+                self.generation.store(generation, Ordering::Release);
+
+                offset
+            }
+        };
+
+        if offset == MISSING {
+            bail!(
+                "no offset for {}.{} in this build, run `btd6-tool offsets` for it",
+                self.class_name(),
+                self.field
+            );
+        }
+
+        Ok(offset)
+    }
+
+    fn resolve(&self) -> Option<u64> {
+        let generated = ACTIVE
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|offsets| offsets.get_field(&self.class_name(), self.field));
+
+        if generated.is_some() {
+            return generated;
+        }
+
+        let dumped = match *ACTIVE_VERSION.read().unwrap() {
+            Some(version) => self.versions.iter().find(|(v, _)| *v == version),
+            None => self.versions.first(),
+        };
+
+        dumped.map(|(_, offset)| *offset)
+    }
+}
+
+/// Registers a `FieldOffset` so that `BuildOffsets::generate` knows which fields to look up
+pub struct FieldKey(pub &'static FieldOffset);
+
+inventory::collect!(FieldKey);
+
+/// the C# name of the field a Rust accessor is bound to, e.g. `bridge` for `InGame::unity_to_simulation`
+pub fn find_bound_field(class_name: &str, accessor: &str) -> Option<&'static str> {
+    inventory::iter::<FieldKey>
+        .into_iter()
+        .find(|FieldKey(field)| field.accessor == accessor && field.class_name() == class_name)
+        .map(|FieldKey(field)| field.field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btd::extensions::IN_GAME_CLASS as IN_GAME;
+
+    /// the layouts of a build that has dropped InGame's fields
+    struct WithoutInGame;
+
+    impl ClassLayouts for WithoutInGame {
+        fn get_field_offset(&self, _namespace: &str, class: &str, field: &str) -> Result<usize> {
+            match class {
+                "InGame" => bail!("no field {}", field),
+                _ => Ok(0x18),
+            }
+        }
+
+        fn get_type_info(&self, _namespace: &str, class: &str) -> Option<u64> {
+            (class == "Simulation").then_some(0x2000)
+        }
+    }
+
+    #[test]
+    fn generates_offsets_for_the_fields_a_build_has() {
+        let (offsets, missing) = BuildOffsets::generate(&WithoutInGame, None);
+
+        assert!(!offsets.fields.contains_key(IN_GAME));
+        assert_eq!(
+            offsets.get_field("Assets.Scripts.Simulation.Simulation", "map"),
+            Some(0x18)
+        );
+
+        assert_eq!(
+            missing,
+            [
+                format!("{}.bridge: no field bridge", IN_GAME),
+                format!("{}.playerContexts: no field playerContexts", IN_GAME),
+                format!(
+                    "{}.stoppedClockForMenuOpen: no field stoppedClockForMenuOpen",
+                    IN_GAME
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn prefers_script_type_infos_to_the_layouts() {
+        let (offsets, _) = BuildOffsets::generate(&WithoutInGame, None);
+        assert_eq!(
+            offsets.type_info,
+            [("Assets.Scripts.Simulation.Simulation".to_string(), 0x2000)].into()
+        );
+
+        let script = Script::parse(
+            r#"{ "ScriptMetadata": [{ "Address": 4096, "Name": "Assets.Scripts.Unity.UI_New.InGame.InGame_TypeInfo" }] }"#,
+        )
+        .unwrap();
+
+        let (offsets, _) = BuildOffsets::generate(&WithoutInGame, Some(&script));
+        assert_eq!(offsets.type_info, [(IN_GAME.to_string(), 0x1000)].into());
+    }
+
+    #[test]
+    fn knows_only_loaded_or_dumped_builds() {
+        testing::load();
+
+        assert!(is_known(&build_id(testing::TIMESTAMP)));
+        assert!(!is_known("00000000"));
+    }
+}
+
+/// A made up build where every bound field gets its own slot, for laying out synthetic objects
+#[cfg(test)]
+pub mod testing {
+    use super::*;
+    use crate::btd::extensions::IN_GAME_CLASS;
+
+    /// the link timestamp of the synthetic build's GameAssembly.dll
+    pub const TIMESTAMP: u32 = 0x5eed_0001;
+
+    /// where the synthetic build keeps InGame's `TypeInfo` pointer
+    pub const IN_GAME_TYPE_OFFSET: u64 = 0x1000;
+
+    const SLOT: u64 = 0x10;
+
+    fn fields() -> Vec<&'static FieldOffset> {
+        let mut fields = inventory::iter::<FieldKey>
+            .into_iter()
+            .map(|FieldKey(field)| *field)
+            .collect::<Vec<_>>();
+
+        fields.sort_by_key(|f| (f.class_name(), f.field));
+        fields.dedup_by_key(|f| (f.class_name(), f.field));
+        fields
+    }
+
+    /// the offset of a bound field, by its C# class (without namespace) and field name
+    pub fn offset(class: &str, field: &str) -> u64 {
+        let index = fields()
+            .iter()
+            .position(|f| f.class == class && f.field == field)
+            .unwrap_or_else(|| panic!("{}.{} isn't bound", class, field));
+
+        SLOT * index as u64
+    }
+
+    /// the size of an object with room for every bound field, after its header
+    pub fn object_size() -> u64 {
+        SLOT * fields().len() as u64
+    }
+
+    /// makes the synthetic build's offsets available to `select`, and selects them
+    pub fn load() {
+        LOADED.get_or_init(|| {
+            let mut offsets = BuildOffsets::default();
+
+            offsets
+                .type_info
+                .insert(IN_GAME_CLASS.to_string(), IN_GAME_TYPE_OFFSET);
+
+            for (index, field) in fields().iter().enumerate() {
+                offsets
+                    .fields
+                    .entry(field.class_name())
+                    .or_default()
+                    .insert(field.field.to_string(), SLOT * index as u64);
+            }
+
+            let mut file = OffsetsFile::default();
+            file.builds.insert(build_id(TIMESTAMP), offsets);
+            file
+        });
+
+        select(Some(&build_id(TIMESTAMP)));
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::BTreeMap, fs::File, marker::PhantomData, os::unix::fs::FileExt, path::Path,
+    sync::Arc,
+};
+
+use anyhow::Context;
+
+use crate::{memory::MemorySource, Result};
+
+/// A process read through `/proc/<pid>`, e.g. BloonsTD6 running under Proton/Wine.
+///
+/// Reading `/proc/<pid>/mem` needs ptrace access to the target, so with `kernel.yama.ptrace_scope = 1`
+/// the tool has to run as root or have `CAP_SYS_PTRACE`.
+#[derive(Debug, Clone)]
+pub struct Process {
+    pid: u32,
+    mem: Arc<File>,
+}
+
+impl Process {
+    pub fn enum_process_ids() -> Vec<u32> {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return vec![];
+        };
+
+        entries
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+            .collect()
+    }
+
+    /// returns the arguments of a process from `/proc/<pid>/cmdline`
+    pub fn get_command_line(pid: u32) -> Result<Vec<String>> {
+        let raw = std::fs::read(format!("/proc/{pid}/cmdline"))?;
+
+        let args = raw
+            .split(|&b| b == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect();
+
+        Ok(args)
+    }
+
+    /// returns the name of a process' executable from `/proc/<pid>/comm`, cut to 15 bytes
+    pub fn get_name(pid: u32) -> Result<String> {
+        let name = std::fs::read_to_string(format!("/proc/{pid}/comm"))?;
+
+        Ok(name.trim_end_matches('\n').to_string())
+    }
+
+    pub fn from_pid(pid: u32) -> Result<Process> {
+        let mem = File::open(format!("/proc/{pid}/mem"))
+            .with_context(|| format!("open /proc/{pid}/mem"))?;
+
+        Ok(Process {
+            pid,
+            mem: Arc::new(mem),
+        })
+    }
+
+    pub fn read_memory(&self, address: u64, buffer: &mut [u8]) -> Result<usize> {
+        Ok(self.mem.read_at(buffer, address)?)
+    }
+
+    /// lists file-backed mappings from `/proc/<pid>/maps`, grouped by path
+    pub fn get_modules(&'_ self) -> Result<Vec<Module<'_>>> {
+        let maps = std::fs::read_to_string(format!("/proc/{}/maps", self.pid))?;
+
+        let mut bounds = BTreeMap::<String, (u64, u64)>::new();
+
+        for line in maps.lines() {
+            let Some((range, path)) = parse_maps_line(line) else {
+                continue;
+            };
+
+            let entry = bounds.entry(path.to_string()).or_insert(range);
+            entry.0 = entry.0.min(range.0);
+            entry.1 = entry.1.max(range.1);
+        }
+
+        let modules = bounds
+            .into_iter()
+            .map(|(path, (start, end))| Module {
+                name: path.rsplit(['/', '\\']).next().unwrap_or(&path).to_string(),
+                base: start,
+                size: end - start,
+                process: PhantomData,
+            })
+            .collect();
+
+        Ok(modules)
+    }
+}
+
+impl MemorySource for Process {
+    fn read_memory(&self, address: u64, buffer: &mut [u8]) -> Result<usize> {
+        Process::read_memory(self, address, buffer)
+    }
+
+    fn is_alive(&self) -> bool {
+        Path::new(&format!("/proc/{}", self.pid)).exists()
+    }
+}
+
+/// parses `start-end perms offset dev inode path`, skipping anonymous and pseudo mappings
+fn parse_maps_line(line: &str) -> Option<((u64, u64), &str)> {
+    let mut parts = line.splitn(6, ' ');
+
+    let (start, end) = parts.next()?.split_once('-')?;
+    let start = u64::from_str_radix(start, 16).ok()?;
+    let end = u64::from_str_radix(end, 16).ok()?;
+
+    let path = parts.nth(4)?.trim_start();
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    Some(((start, end), path))
+}
+
+pub struct Module<'a> {
+    name: String,
+    base: u64,
+    size: u64,
+    process: PhantomData<&'a Process>,
+}
+
+impl<'a> Module<'a> {
+    pub fn get_base_name(&self) -> Result<String> {
+        Ok(self.name.clone())
+    }
+
+    pub fn get_bounds(&self) -> Result<(u64, u64)> {
+        Ok((self.base, self.size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        os::unix::process::CommandExt,
+        process::{Child, Command},
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::btd;
+
+    /// kills the child when dropped, so a failed assertion doesn't leave it running
+    struct Spawned(Child);
+
+    impl Drop for Spawned {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    /// `sleep` started the way wine starts the game: as BloonsTD6.exe, with a windows argv[0]
+    fn spawn(executable: &Path) -> Spawned {
+        let child = Command::new(executable)
+            .arg0("Z:\\games\\BloonsTD6\\BloonsTD6.exe")
+            .arg("60")
+            .spawn()
+            .unwrap();
+
+        // the command line can read as empty until the new process has finished starting
+        for _ in 0..100 {
+            if Process::get_command_line(child.id()).is_ok_and(|args| !args.is_empty()) {
+                break;
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        Spawned(child)
+    }
+
+    #[test]
+    fn finds_and_reads_the_game_process() {
+        let dir = std::env::temp_dir().join(format!("btd6-tool-procfs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let executable = dir.join("BloonsTD6.exe");
+        fs::copy("/bin/sleep", &executable).unwrap();
+
+        // a process that only claims to be the game in its arguments isn't picked
+        let decoy = spawn(Path::new("/bin/sleep"));
+        let game = spawn(&executable);
+
+        let decoy_pid = decoy.0.id();
+        let game_pid = game.0.id();
+
+        assert_eq!(Process::get_name(decoy_pid).unwrap(), "sleep");
+        assert_eq!(Process::get_name(game_pid).unwrap(), "BloonsTD6.exe");
+        assert_eq!(
+            Process::get_command_line(game_pid).unwrap(),
+            ["Z:\\games\\BloonsTD6\\BloonsTD6.exe", "60"]
+        );
+
+        assert!(btd::is_bloons_process(game_pid));
+        assert!(!btd::is_bloons_process(decoy_pid));
+
+        // the executable is mapped like GameAssembly.dll is under wine
+        let process = Process::from_pid(game_pid).unwrap();
+        let modules = process.get_modules().unwrap();
+        let module = modules
+            .iter()
+            .find(|m| m.get_base_name().unwrap() == "BloonsTD6.exe")
+            .unwrap();
+
+        let (base, size) = module.get_bounds().unwrap();
+        assert!(size > 0);
+
+        let mut magic = [0; 4];
+        assert_eq!(process.read_memory(base, &mut magic).unwrap(), 4);
+        assert_eq!(&magic, b"\x7fELF");
+        assert!(process.is_alive());
+
+        drop(game);
+        drop(decoy);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!process.is_alive());
+    }
+}
@@ -1,22 +1,36 @@
-use std::mem::{size_of, size_of_val};
+use std::{
+    mem::{size_of, size_of_val},
+    sync::Arc,
+};
 
 use windows::Win32::{
-    Foundation::{HANDLE, HMODULE},
+    Foundation::{CloseHandle, HANDLE, HMODULE, STILL_ACTIVE},
     System::{
         Diagnostics::Debug::ReadProcessMemory,
         ProcessStatus::{
             EnumProcessModules, EnumProcesses, GetModuleBaseNameA, GetModuleInformation,
             GetProcessImageFileNameA, MODULEINFO,
         },
-        Threading::{OpenProcess, PROCESS_ACCESS_RIGHTS},
+        Threading::{GetExitCodeProcess, OpenProcess, PROCESS_ACCESS_RIGHTS},
     },
 };
 
-use crate::Result;
+use crate::{memory::MemorySource, Result};
+
+/// A process handle, closed when dropped
+#[derive(Debug)]
+struct OwnedHandle(HANDLE);
+
+impl Drop for OwnedHandle {
+    fn drop(&mut self) {
+        let _ = unsafe { CloseHandle(self.0) };
+    }
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An open process, whose handle is closed once every clone of it has been dropped
+#[derive(Debug, Clone)]
 pub struct Process {
-    handle: HANDLE,
+    handle: Arc<OwnedHandle>,
 }
 
 impl Process {
@@ -39,12 +53,18 @@ impl Process {
     pub fn from_pid(pid: u32, access: PROCESS_ACCESS_RIGHTS) -> Result<Process> {
         let handle = unsafe { OpenProcess(access, false, pid) }?;
 
-        Ok(Process { handle })
+        Ok(Process {
+            handle: Arc::new(OwnedHandle(handle)),
+        })
+    }
+
+    fn handle(&self) -> HANDLE {
+        self.handle.0
     }
 
     pub fn get_image_file_name(&self) -> Result<String> {
         let mut name = [0u8; 1024];
-        let len = unsafe { GetProcessImageFileNameA(self.handle, &mut name) } as usize;
+        let len = unsafe { GetProcessImageFileNameA(self.handle(), &mut name) } as usize;
 
         Ok(String::from_utf8(name[0..len].to_vec())?)
     }
@@ -54,7 +74,7 @@ impl Process {
 
         unsafe {
             ReadProcessMemory(
-                self.handle,
+                self.handle(),
                 address as _,
                 buffer.as_mut_ptr() as _,
                 buffer.len(),
@@ -71,7 +91,7 @@ impl Process {
 
         unsafe {
             EnumProcessModules(
-                self.handle,
+                self.handle(),
                 modules.as_mut_ptr() as _,
                 size_of_val(&modules) as u32,
                 &mut output,
@@ -90,6 +110,19 @@ impl Process {
     }
 }
 
+impl MemorySource for Process {
+    fn read_memory(&self, address: u64, buffer: &mut [u8]) -> Result<usize> {
+        Process::read_memory(self, address, buffer)
+    }
+
+    fn is_alive(&self) -> bool {
+        let mut exit_code = 0;
+        let result = unsafe { GetExitCodeProcess(self.handle(), &mut exit_code) };
+
+        result.is_ok() && exit_code == STILL_ACTIVE.0 as u32
+    }
+}
+
 pub struct Module<'a> {
     process: &'a Process,
     handle: HMODULE,
@@ -99,7 +132,7 @@ impl<'a> Module<'a> {
     pub fn get_base_name(&self) -> Result<String> {
         let mut file_name = [0u8; 1024];
         let len = unsafe {
-            GetModuleBaseNameA(self.process.handle, self.handle, &mut file_name) as usize
+            GetModuleBaseNameA(self.process.handle(), self.handle, &mut file_name) as usize
         };
 
         let string = String::from_utf8(file_name[0..len].to_vec())
@@ -113,7 +146,7 @@ impl<'a> Module<'a> {
 
         unsafe {
             GetModuleInformation(
-                self.process.handle,
+                self.process.handle(),
                 self.handle,
                 &mut info,
                 size_of_val(&info) as u32,
@@ -0,0 +1,448 @@
+//! A synthetic il2cpp heap held in a `RegionMemory`, laid out with the made up build's offsets
+//! from `offsets::testing`
+
+use std::collections::HashMap;
+
+use crate::{
+    btd::BloonsGame,
+    memory::{Pointer, ProcessMemoryView, RegionMemory},
+    offsets::testing::{self, IN_GAME_TYPE_OFFSET, TIMESTAMP},
+};
+
+/// where the synthetic GameAssembly.dll is loaded
+pub const MODULE: u64 = 0x1_0000_0000;
+
+const MODULE_SIZE: u64 = 0x10000;
+
+const HEAP: u64 = 0x10_0000;
+
+const HEAP_SIZE: u64 = 0x40_0000;
+
+/// the size of the PE headers' optional header
+const OPTIONAL_HEADER_SIZE: u64 = 240;
+
+/// section characteristics of initialized, writable data
+pub const DATA: u32 = 0xC000_0040;
+
+/// section characteristics of executable code
+pub const CODE: u32 = 0x6000_0020;
+
+/// where `register_classes` puts the metadata registration and the pointer to the class table
+pub const REGISTRATION: u64 = MODULE + 0x2300;
+pub const CLASS_TABLE_SLOT: u64 = MODULE + 0x3800;
+
+/// the metadata handle of the first type definition, with the rest 88 bytes apart
+const METADATA_HANDLES: u64 = 0x5000_0000;
+
+/// how many type definitions the synthetic build has, the same in every heap like a real build
+pub const TYPE_DEFINITIONS: u64 = 0x100;
+
+/// attributes of a field's `Il2CppType`
+pub const FIELD_STATIC: u32 = 0x10;
+pub const FIELD_LITERAL: u32 = 0x40;
+
+/// `Il2CppTypeEnum` of a class and of an int
+pub const KIND_CLASS: u32 = 0x12;
+pub const KIND_I4: u32 = 0x08;
+
+pub struct Heap {
+    pub memory: RegionMemory,
+    next: u64,
+    classes: HashMap<String, u64>,
+    sections: u16,
+}
+
+impl Heap {
+    pub fn new() -> Self {
+        testing::load();
+
+        let memory = RegionMemory::new()
+            .with_region(MODULE, vec![0; MODULE_SIZE as usize])
+            .with_region(HEAP, vec![0; HEAP_SIZE as usize]);
+
+        let mut heap = Self {
+            memory,
+            next: HEAP,
+            classes: HashMap::new(),
+            sections: 0,
+        };
+
+        heap.write(MODULE, b"MZ");
+        heap.write_u32(MODULE + 0x3c, 0x80);
+        heap.write_u32(MODULE + 0x80, 0x4550);
+        heap.write(MODULE + 0x84, &0x8664u16.to_le_bytes());
+        heap.write_u32(MODULE + 0x88, TIMESTAMP);
+        heap.write(MODULE + 0x94, &(OPTIONAL_HEADER_SIZE as u16).to_le_bytes());
+        heap.write(MODULE + 0x98, &0x20bu16.to_le_bytes());
+        heap.write_u64(MODULE + 0x98 + 24, MODULE);
+
+        heap
+    }
+
+    /// adds a section to the module's headers, covering the module's memory from `rva`
+    pub fn section(&mut self, name: &str, rva: u32, size: u32, characteristics: u32) {
+        let header = MODULE + 0x98 + OPTIONAL_HEADER_SIZE + 40 * self.sections as u64;
+
+        let mut padded = name.as_bytes().to_vec();
+        padded.resize(8, 0);
+        self.write(header, &padded);
+        self.write_u32(header + 8, size);
+        self.write_u32(header + 12, rva);
+        self.write_u32(header + 16, size);
+        self.write_u32(header + 20, rva);
+        self.write_u32(header + 36, characteristics);
+
+        self.sections += 1;
+        self.write(MODULE + 0x86, &self.sections.to_le_bytes());
+    }
+
+    /// puts every class made so far in a class table, along with the data section and metadata
+    /// registration `ClassRegistry::locate` finds it by, giving each class a metadata handle
+    pub fn register_classes(&mut self) -> u64 {
+        if self.sections == 0 {
+            self.section(".data", 0x1000, 0x3000, DATA);
+        }
+
+        let mut classes = self.classes.values().copied().collect::<Vec<_>>();
+        classes.sort();
+
+        let count = TYPE_DEFINITIONS;
+        let pairs = [(0, 0), (0, 0), (0, 0), (count, MODULE + 0x2000), (0, 0)]
+            .into_iter()
+            .chain([(count, MODULE + 0x2000); 2]);
+
+        for (i, (count, pointer)) in pairs.enumerate() {
+            self.write_u64(REGISTRATION + 16 * i as u64, count);
+            self.write_u64(REGISTRATION + 16 * i as u64 + 8, pointer);
+        }
+
+        let table = self.alloc(8 * count);
+        for (i, &class) in classes.iter().enumerate() {
+            let handle = METADATA_HANDLES + 88 * i as u64;
+
+            // the class's own Il2CppType refers to it by handle too
+            self.write_u64(class + 0x68, handle);
+            self.write_u64(class + 0x20, handle);
+            self.write_u32(class + 0x28, KIND_CLASS << 16);
+            self.write_u64(table + 8 * i as u64, class);
+        }
+
+        self.write_u64(CLASS_TABLE_SLOT, table);
+        table
+    }
+
+    /// an `Il2CppType` of a kind with its data and attributes
+    pub fn type_ref(&mut self, kind: u32, data: u64, attributes: u32) -> u64 {
+        let address = self.alloc(0x10);
+        self.write_u64(address, data);
+        self.write_u32(address + 8, (kind << 16) | attributes);
+        address
+    }
+
+    /// sets the fields a class declares, each a name, `Il2CppType` and offset
+    pub fn fields(&mut self, class: &str, fields: &[(&str, u64, i32)]) {
+        let class = self.class(class, None);
+        let infos = self.alloc(0x20 * fields.len() as u64);
+
+        for (i, &(name, type_ref, offset)) in fields.iter().enumerate() {
+            let info = infos + 0x20 * i as u64;
+            let name = self.c_string(name);
+
+            self.write_u64(info, name);
+            self.write_u64(info + 8, type_ref);
+            self.write_u64(info + 0x10, class);
+            self.write_u32(info + 0x18, offset as u32);
+        }
+
+        self.write_u64(class + 0x80, infos);
+        self.write_u32(class + 0x124, fields.len() as u32);
+    }
+
+    pub fn alloc(&mut self, size: u64) -> u64 {
+        let address = self.next;
+        self.next += (size + 0x1f) & !0xf;
+        address
+    }
+
+    pub fn write(&mut self, address: u64, data: &[u8]) {
+        self.memory.write(address, data).unwrap()
+    }
+
+    pub fn write_u64(&mut self, address: u64, value: u64) {
+        self.write(address, &value.to_le_bytes())
+    }
+
+    pub fn write_u32(&mut self, address: u64, value: u32) {
+        self.write(address, &value.to_le_bytes())
+    }
+
+    pub fn c_string(&mut self, value: &str) -> u64 {
+        let address = self.alloc(value.len() as u64 + 1);
+        self.write(address, value.as_bytes());
+        address
+    }
+
+    /// the `TypeInfo` for a class, created the first time it's asked for
+    pub fn class_in(&mut self, namespace: &str, name: &str, base: Option<&str>) -> u64 {
+        if let Some(&class) = self.classes.get(name) {
+            return class;
+        }
+
+        let base = base.map(|base| self.class(base, None));
+
+        let class = self.alloc(0x140);
+        let name_address = self.c_string(name);
+        let namespace_address = self.c_string(namespace);
+
+        self.write_u64(class + 0x10, name_address);
+        self.write_u64(class + 0x18, namespace_address);
+        if let Some(base) = base {
+            self.write_u64(class + 0x58, base);
+        }
+        self.write_u64(class + 0x78, class);
+
+        self.classes.insert(name.to_string(), class);
+        class
+    }
+
+    pub fn class(&mut self, name: &str, base: Option<&str>) -> u64 {
+        self.class_in("", name, base)
+    }
+
+    /// an object with room for every bound field
+    pub fn object(&mut self, class: &str, base: Option<&str>) -> u64 {
+        let class = self.class(class, base);
+
+        let object = self.alloc(0x10 + testing::object_size());
+        self.write_u64(object, class);
+        object
+    }
+
+    /// the address of a bound field in an object
+    pub fn field(&self, object: u64, class: &str, field: &str) -> u64 {
+        object + 0x10 + testing::offset(class, field)
+    }
+
+    pub fn set(&mut self, object: u64, class: &str, field: &str, value: u64) {
+        self.write_u64(self.field(object, class, field), value)
+    }
+
+    pub fn set_u32(&mut self, object: u64, class: &str, field: &str, value: u32) {
+        self.write_u32(self.field(object, class, field), value)
+    }
+
+    pub fn set_f32(&mut self, object: u64, class: &str, field: &str, value: f32) {
+        self.write_u32(self.field(object, class, field), value.to_bits())
+    }
+
+    pub fn string(&mut self, value: &str) -> u64 {
+        let class = self.class("String", None);
+        let units = value.encode_utf16().collect::<Vec<_>>();
+
+        let string = self.alloc(0x14 + 2 * units.len() as u64 + 2);
+        self.write_u64(string, class);
+        self.write_u32(string + 0x10, units.len() as u32);
+
+        let bytes = units
+            .iter()
+            .flat_map(|u| u.to_le_bytes())
+            .collect::<Vec<_>>();
+        self.write(string + 0x14, &bytes);
+
+        string
+    }
+
+    /// an array of `length` elements of `stride` bytes, left zeroed
+    pub fn array_of(&mut self, element: &str, stride: u64, length: u64) -> u64 {
+        self.class("Array", None);
+        let class = self.class(&format!("{}[]", element), Some("Array"));
+
+        let array = self.alloc(0x20 + stride * length.max(1));
+        self.write_u64(array, class);
+        self.write_u64(array + 0x18, length);
+        array
+    }
+
+    pub fn array(&mut self, element: &str, items: &[u64]) -> u64 {
+        let array = self.array_of(element, 8, items.len() as u64);
+
+        for (i, &item) in items.iter().enumerate() {
+            self.write_u64(array + 0x20 + 8 * i as u64, item);
+        }
+
+        array
+    }
+
+    pub fn array_i32(&mut self, items: &[i32]) -> u64 {
+        let array = self.array_of("Int32", 4, items.len() as u64);
+
+        for (i, &item) in items.iter().enumerate() {
+            self.write_u32(array + 0x20 + 4 * i as u64, item as u32);
+        }
+
+        array
+    }
+
+    pub fn list(&mut self, element: &str, items: &[u64]) -> u64 {
+        let array = self.array(element, items);
+
+        let list = self.object("List`1", None);
+        self.write_u64(list + 0x10, array);
+        self.write_u32(list + 0x18, items.len() as u32);
+        list
+    }
+
+    /// a `Dictionary<K, V>` of pointers, with no free entries
+    pub fn dictionary(&mut self, entries: &[(u64, u64)]) -> u64 {
+        let array = self.array_of("Entry", 0x18, entries.len() as u64);
+
+        for (i, &(key, value)) in entries.iter().enumerate() {
+            let entry = array + 0x20 + 0x18 * i as u64;
+            self.write_u32(entry, 1);
+            self.write_u32(entry + 4, u32::MAX);
+            self.write_u64(entry + 8, key);
+            self.write_u64(entry + 16, value);
+        }
+
+        let dictionary = self.object("Dictionary`2", None);
+        self.write_u64(dictionary + 0x18, array);
+        self.write_u32(dictionary + 0x20, entries.len() as u32);
+        dictionary
+    }
+
+    pub fn view(&self) -> ProcessMemoryView {
+        ProcessMemoryView::new(self.memory.clone())
+    }
+
+    /// a binding of the object at `address`, read without caching
+    pub fn get<T: TryFrom<Pointer, Error = anyhow::Error>>(&self, address: u64) -> T {
+        let memory = self.view().without_cache();
+        T::try_from(Pointer { memory, address }).unwrap()
+    }
+
+    pub fn game(&self) -> BloonsGame {
+        BloonsGame::new(self.view(), MODULE)
+    }
+}
+
+/// A match on Monkey Meadow with one dart monkey, 650 cash and one upgrade available
+pub struct Scene {
+    pub heap: Heap,
+    /// the `UnityToSimulation` between InGame and the simulation
+    pub bridge: u64,
+    pub simulation: u64,
+    /// the address of the simulation's elapsed time
+    pub time: u64,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        let mut h = Heap::new();
+
+        let ingame_class = h.class_in("Assets.Scripts.Unity.UI_New.InGame", "InGame", None);
+        let statics = h.alloc(0x10);
+        h.write_u64(ingame_class + 0xb8, statics);
+        h.write_u64(MODULE + IN_GAME_TYPE_OFFSET, ingame_class);
+
+        let ingame = h.object("InGame", None);
+        h.write_u64(statics, ingame);
+
+        let input = h.object("InputManager", None);
+        let context = h.object("Context", None);
+        h.set(context, "PlayerContext.Context", "inputManager", input);
+        let contexts = h.list("Context", &[context]);
+        h.set(ingame, "InGame", "playerContexts", contexts);
+
+        let bridge = h.object("UnityToSimulation", None);
+        h.set(ingame, "InGame", "bridge", bridge);
+        let simulation = h.object("Simulation", None);
+        h.set(bridge, "UnityToSimulation", "simulation", simulation);
+
+        let time = h.object("SimulationTime", None);
+        h.set_u32(time, "SimulationTime", "elapsed", 100);
+        h.set(simulation, "Simulation", "time", time);
+
+        let model = h.object("GameModel", None);
+        h.set(simulation, "Simulation", "model", model);
+        let map_model = h.object("MapModel", None);
+        let name = h.string("Monkey Meadow");
+        h.set(map_model, "MapModel", "mapName", name);
+        h.set(model, "GameModel", "map", map_model);
+        let mode = h.string("Standard");
+        h.set(model, "GameModel", "gameMode", mode);
+        h.set(model, "GameModel", "gameType", mode);
+        h.set_u32(model, "GameModel", "randomSeed", 42);
+
+        h.class("Model", None);
+        let upgrade = h.object("UpgradeModel", Some("Model"));
+        let name = h.string("Sharp Shots");
+        h.set(upgrade, "Model", "_name", name);
+        h.set_u32(upgrade, "UpgradeModel", "cost", 140);
+        h.set_u32(upgrade, "UpgradeModel", "path", 0);
+        h.set_u32(upgrade, "UpgradeModel", "tier", 2);
+        let upgrades = h.array("UpgradeModel", &[upgrade]);
+        h.set(model, "GameModel", "upgrades", upgrades);
+
+        let cash_manager = h.object("CashManager", None);
+        let cash = h.object("KonFuze", None);
+        let honey = h.field(cash, "KonFuze", "honey");
+        h.write_u64(honey, 650f64.to_bits());
+        h.write_u64(cash_manager + 0x10, cash);
+        let key = h.object("Object", None);
+        let cash_managers = h.dictionary(&[(key, cash_manager)]);
+        h.set(simulation, "Simulation", "cashManagers", cash_managers);
+
+        h.class("RootObject", None);
+        h.class("RootBehavior", Some("RootObject"));
+        let map = h.object("Map", Some("RootBehavior"));
+        h.set(simulation, "Simulation", "map", map);
+
+        h.class("EntityModel", None);
+        let tower_model = h.object("TowerModel", Some("EntityModel"));
+        let base_id = h.string("DartMonkey");
+        h.set(tower_model, "EntityModel", "baseId", base_id);
+        h.set_u32(tower_model, "TowerModel", "towerSet", 1);
+        let tiers = h.array_i32(&[1, 0, 2]);
+        h.set(tower_model, "TowerModel", "tiers", tiers);
+        let upgrade_path = h.object("UpgradePathModel", None);
+        let name = h.string("Sharp Shots");
+        h.set(upgrade_path, "UpgradePathModel", "upgrade", name);
+        let upgrade_paths = h.array("UpgradePathModel", &[upgrade_path]);
+        h.set(tower_model, "TowerModel", "upgrades", upgrade_paths);
+        let applied = h.string("Sharp Shots");
+        let applied = h.array("String", &[applied]);
+        h.set(tower_model, "TowerModel", "appliedUpgrades", applied);
+
+        let tower = h.object("Tower", None);
+        let id = h.string("tower-1");
+        h.set(tower, "Tower", "uniqueId", id);
+        h.set_f32(tower, "Tower", "worth", 350.0);
+        h.set(tower, "Tower", "towerModel", tower_model);
+        h.set_u32(tower, "Tower", "areaPlacedOn", 3);
+        let towers = h.list("Tower", &[tower]);
+        let area = h.object("Object", None);
+        let area_towers = h.dictionary(&[(area, towers)]);
+        h.set(map, "Map", "areaTowers", area_towers);
+
+        let segment = h.object("PathSegment", None);
+        let bloons = h.list("BloonTargetProxy", &[]);
+        h.set(segment, "PathSegment", "bloons", bloons);
+        h.set_f32(segment, "PathSegment", "distanceUntilLeak", 100.0);
+        let segments = h.array("PathSegment", &[segment]);
+        let path = h.object("Path", None);
+        h.set(path, "Path", "segments", segments);
+        let paths = h.list("Path", &[path]);
+        let path_manager = h.object("PathManager", None);
+        h.set(path_manager, "PathManager", "paths", paths);
+        h.set(map, "Map", "pathManager", path_manager);
+
+        let time = h.field(time, "SimulationTime", "elapsed");
+
+        Self {
+            heap: h,
+            bridge,
+            simulation,
+            time,
+        }
+    }
+}
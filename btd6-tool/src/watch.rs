@@ -0,0 +1,168 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    btd::{BloonsGame, TickKey},
+    inspect::Inspector,
+    Previous, Result,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Reads a path of fields once per simulation tick, printing its value whenever it changes
+pub struct Watcher {
+    inspector: Inspector,
+    path: String,
+    csv: Option<BufWriter<File>>,
+    tick: Previous<TickKey>,
+    value: Previous<String>,
+}
+
+impl Watcher {
+    pub fn new(game: BloonsGame, path: &str, csv: Option<&str>) -> Result<Self> {
+        let csv = match csv {
+            Some(csv) => {
+                let mut csv = BufWriter::new(File::create(csv)?);
+                writeln!(csv, "elapsed,{}", escape_csv(path))?;
+                Some(csv)
+            }
+
+            None => None,
+        };
+
+        Ok(Self {
+            inspector: Inspector::new(game)?,
+            path: path.to_string(),
+            csv,
+            tick: Default::default(),
+            value: Default::default(),
+        })
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        loop {
+            if let Some(line) = self.poll()? {
+                println!("{}", line);
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// reads the path if the simulation has ticked, returning the line to print if its value changed
+    fn poll(&mut self) -> Result<Option<String>> {
+        let game = self.inspector.game();
+        game.invalidate();
+
+        // wait out menus and loading screens
+        let Ok(Some(key)) = game.try_get_tick_key() else {
+            return Ok(None);
+        };
+
+        if !self.tick.set(key) {
+            return Ok(None);
+        }
+
+        let value = self
+            .inspector
+            .read_path(&self.path)
+            .unwrap_or_else(|e| format!("<{:#}>", e));
+
+        if !self.value.set(value.clone()) {
+            return Ok(None);
+        }
+
+        if let Some(csv) = &mut self.csv {
+            writeln!(csv, "{},{}", key.elapsed, escape_csv(&value))?;
+            csv.flush()?;
+        }
+
+        Ok(Some(format!("{:>8} {}", key.elapsed, value)))
+    }
+}
+
+/// quotes a CSV field if it contains a separator, quote or line break
+fn escape_csv(value: &str) -> String {
+    match value.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        offsets,
+        testing::{Scene, KIND_I4},
+    };
+
+    /// a match whose InGame has a `towerCount`, kept in a slot InGame has no bound field in
+    fn scene() -> (Scene, u64) {
+        let mut scene = Scene::new();
+        let h = &mut scene.heap;
+
+        h.register_classes();
+
+        let offset = 0x10 + offsets::testing::offset("Tower", "worth");
+        let int = h.type_ref(KIND_I4, 0, 0);
+        h.fields("InGame", &[("towerCount", int, offset as i32)]);
+
+        let ingame = h.game().get_ingame().unwrap().unwrap().0.address;
+
+        (scene, ingame + offset)
+    }
+
+    /// polls the heap as it is now, since each view of it is a copy
+    fn poll(watcher: &mut Watcher, scene: &Scene) -> Option<String> {
+        watcher.inspector = Inspector::new(scene.heap.game()).unwrap();
+        watcher.poll().unwrap()
+    }
+
+    #[test]
+    fn writes_values_when_they_change_on_a_new_tick() {
+        let (mut scene, tower_count) = scene();
+        let path = std::env::temp_dir().join(format!("btd6-tool-{}-watch.csv", std::process::id()));
+
+        let mut watcher = Watcher::new(
+            scene.heap.game(),
+            "towerCount",
+            Some(path.to_str().unwrap()),
+        )
+        .unwrap();
+
+        scene.heap.write_u32(tower_count, 1);
+        assert_eq!(poll(&mut watcher, &scene).as_deref(), Some("     100 1"));
+
+        // the same tick isn't read again, even if the value has changed since
+        scene.heap.write_u32(tower_count, 2);
+        assert_eq!(poll(&mut watcher, &scene), None);
+
+        // a new tick with the same value isn't written either
+        scene.heap.write_u32(tower_count, 1);
+        scene.heap.write_u32(scene.time, 101);
+        assert_eq!(poll(&mut watcher, &scene), None);
+
+        scene.heap.write_u32(tower_count, 3);
+        scene.heap.write_u32(scene.time, 102);
+        assert_eq!(poll(&mut watcher, &scene).as_deref(), Some("     102 3"));
+
+        drop(watcher);
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(csv, "elapsed,towerCount\n100,1\n102,3\n");
+    }
+
+    #[test]
+    fn quotes_csv_fields_with_separators_and_quotes() {
+        assert_eq!(escape_csv("towerCount"), "towerCount");
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv("two\nlines"), "\"two\nlines\"");
+    }
+}
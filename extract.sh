@@ -1,5 +1,31 @@
-cd "../Il2CppDumper/Il2CppDumper/bin/Debug/net8.0/"
-./Il2CppDumper.exe "C:/Program Files/Epic Games/BloonsTD6/GameAssembly.dll" "C:/Program Files/Epic Games/BloonsTD6/BloonsTD6_Data/il2cpp_data/Metadata/global-metadata.dat"
-cp "dump.cs" "../../../../../testing/btd6-tool-bindgen/"
+GAME="C:/Program Files/Epic Games/BloonsTD6"
+GAME_ASSEMBLY="$GAME/GameAssembly.dll"
+METADATA="$GAME/BloonsTD6_Data/il2cpp_data/Metadata/global-metadata.dat"
 
-ssh box "cat /mnt/e/persistent/code/btd6-tool/Il2CppDumper/Il2CppDumper/bin/Debug/net8.0/script.json | jq '.ScriptMetadata[] | select(.Name == \"Assets.Scripts.Unity.UI_New.InGame.InGame_TypeInfo\") | .Address'"
+# adds the installed build's field offsets and TypeInfo addresses to offsets.json, straight from the game files
+cargo run --release -- offsets "$GAME_ASSEMBLY" "$METADATA"
+
+# `./extract.sh dump <version>` also adds Il2CppDumper's dump.cs of the installed build, which the bindings are
+# checked against when they're compiled
+if [ "$1" = "dump" ]; then
+    VERSION="$2"
+    if [ -z "$VERSION" ]; then
+        echo "usage: $0 dump <version>" >&2
+        exit 1
+    fi
+
+    IL2CPPDUMPER="${IL2CPPDUMPER:-../Il2CppDumper/Il2CppDumper/bin/Debug/net8.0/Il2CppDumper.exe}"
+    OUTPUT=$(mktemp -d)
+
+    "$IL2CPPDUMPER" "$GAME_ASSEMBLY" "$METADATA" "$OUTPUT" || exit 1
+
+    mkdir -p btd6-tool-bindgen/dumps
+    cp "$OUTPUT/dump.cs" "btd6-tool-bindgen/dumps/$VERSION.cs"
+    rm -r "$OUTPUT"
+
+    # builds are named by GameAssembly.dll's PE timestamp in hex
+    PE_HEADER=$(od -An -tu4 -j60 -N4 "$GAME_ASSEMBLY" | tr -d ' ')
+    BUILD_ID=$(od -An -tx4 -j$((PE_HEADER + 8)) -N4 "$GAME_ASSEMBLY" | tr -d ' ')
+
+    echo "$BUILD_ID $VERSION" >> btd6-tool-bindgen/dumps/builds.txt
+fi